@@ -23,3 +23,173 @@ cookies_file = ملف الكوكيز
 embed-thumbnail = تضمين الصورة المصغرة
 advanced-options = خيارات متقدمة
 playlist = قائمة تشغيل
+config_parse_error = يحتوي config.toml على أخطاء، لن يتم حفظ التغييرات حتى يتم إصلاحها: { $error }
+cookies = الكوكيز
+cookies_browser = من المتصفح
+browser = المتصفح
+keyring = حلقة المفاتيح
+keyring_automatic = تلقائي
+browser_profile = الملف الشخصي (اختياري)
+browser_container = الحاوية (اختياري)
+check = فحص
+checking = جارٍ الفحص...
+cookies_check_summary = تم استخراج { $total } من الكوكيز، { $domain_cookies } منها لـ { $domain }
+cookies_check_logged_in = تم العثور على كوكيز تسجيل الدخول
+cookies_check_not_logged_in = لم يتم العثور على كوكيز تسجيل الدخول
+site_logins = تسجيل الدخول للمواقع
+add_login = إضافة حساب
+login_site = الموقع (مثال: vimeo.com)
+login_machine = مستخرج yt-dlp (مثال: vimeo)
+login_machine_missing = لن يُستخدم هذا الحساب حتى يُحدد مستخرج yt-dlp الخاص به، وهو اسم الجهاز في ملف netrc للمستخرج مثل watchnebula لموقع nebula.tv
+login_username = اسم المستخدم
+login_password = كلمة المرور
+preset = الإعداد المسبق
+no_preset = بدون إعداد مسبق
+network = الشبكة
+override_network_for_preset = تجاوز إعدادات الشبكة للإعداد المسبق "{ $preset }"
+proxy = الوكيل (Proxy)
+source_address = عنوان المصدر
+ip_version = إصدار IP
+ip_version_any = أي إصدار
+socket_timeout = مهلة الاتصال (بالثواني)
+retries = عدد المحاولات
+fragment_retries = محاولات الأجزاء
+limit_rate = حد السرعة (مثال: 2M)
+add_to_queue = إضافة إلى قائمة الانتظار
+queue_paused = قائمة الانتظار متوقفة بسبب جدول السرعة
+queued = { $count } في قائمة الانتظار
+active_rule = الجدول: { $rule }
+bandwidth_schedule = جدول سرعة التنزيل
+bandwidth_schedule_help = قاعدة في كل سطر: [الأيام] HH:MM-HH:MM unlimited|pause|السرعة، مثال: "Sat,Sun 10:00-18:00 pause". يتم تطبيق أول قاعدة مطابقة
+add_rule = إضافة قاعدة
+download_later = التنزيل لاحقاً
+cancel = إلغاء
+start_at = البدء في
+schedule = جدولة
+stop = إيقاف
+queue = قائمة الانتظار
+downloading_now = يتم التنزيل الآن
+queued_downloads = في الانتظار
+scheduled_downloads = مجدولة
+queue_empty = لا توجد تنزيلات في قائمة الانتظار
+subscriptions = الاشتراكات
+subscription_url = رابط القناة أو قائمة التشغيل
+subscribe = اشتراك
+skip_existing_entries = تنزيل المقاطع الجديدة فقط
+subscriptions_help = تستخدم الاشتراكات الجديدة نوع التنزيل والإعداد المسبق ومسار الحفظ الحاليين. لقنوات يوتيوب استخدم رابط تبويب الفيديوهات، مثال: https://www.youtube.com/@channel/videos
+sync_interval = التحقق كل (دقائق)
+subscription_status = آخر تحقق: { $last_checked }، { $count } جديد
+never_checked = لم يتم التحقق بعد
+subscription_new_items = { $count } عناصر جديدة من { $url }
+livestream = بث مباشر
+live_from_start = التسجيل من البداية
+wait_for_video = التحقق من البث كل (ثوانٍ)
+max_recording_duration = إيقاف التسجيل بعد (دقائق)
+waiting_for_stream = في انتظار بدء البث...
+finishing_recording = جارٍ إنهاء التسجيل...
+recording_saved = تم حفظ التسجيل!
+download_sections = المقاطع
+chapters_regex = الفصول (الاسم أو تعبير نمطي)
+force_keyframes_at_cuts = قص دقيق (أبطأ)
+fetch_info = جلب المعلومات
+fetching_info = جارٍ جلب المعلومات...
+embed_chapters = تضمين الفصول
+split_chapters = التقسيم حسب الفصول
+chapter_template = قالب اسم ملف الفصل
+embed_metadata = تضمين البيانات الوصفية
+fill_from_video = التعبئة من الفيديو
+tag_artist = الفنان
+tag_album = الألبوم
+tag_title = العنوان
+tag_track = المسار (مثال: 3/12)
+tag_year = السنة
+tag_genre = النوع
+write_thumbnail = حفظ الصورة المصغرة
+write_all_thumbnails = حفظ جميع الصور المصغرة
+thumbnail_original_format = الصيغة الأصلية
+square_thumbnail = غلاف مربع
+thumbnail_embed_unsupported = لا يمكن تضمين الصور المصغرة في هذه الصيغة (WAV و AAC و WEBM و AVI و FLV غير مدعومة)
+format_selector = الصيغة (-f)
+format_sort = الترتيب (-S)
+test_format = اختبار
+testing_format = جارٍ تحديد الصيغة...
+format_resolved = المختار: { $formats }
+format_test_needs_info = اجلب معلومات الفيديو أولاً لاختبار الصيغة عليها
+codec_any = أي
+video_codec = ترميز الفيديو
+audio_codec = ترميز الصوت
+max_fps = أقصى معدل إطارات
+dynamic_range = النطاق الديناميكي
+resolution_best = أفضل دقة متاحة
+strict_resolution = الدقة المحددة فقط
+audio_only_fallback = الصوت فقط إن لم يتوفر فيديو
+download_finished_resolution = اكتمل التنزيل! ({ $resolution })
+bitrate = معدل البت
+bitrate_variable = متغير (حسب الجودة)
+sample_rate = معدل العينة
+channels = القنوات
+audio_original = الأصلي
+audio_mono = أحادي
+audio_stereo = ستيريو
+audio_format_best = الأصلية (بدون إعادة ترميز)
+recode_warning = تحتاج هذه الصيغة إلى إعادة ترميز الفيديو، وهذا أبطأ بكثير من التنزيل
+normalize_loudness = توحيد مستوى الصوت (EBU R128)
+loudness_target = المستوى المستهدف (LUFS)
+true_peak = الذروة الحقيقية (dBTP)
+normalizing = جارٍ توحيد مستوى الصوت…
+pp_merging = جارٍ دمج الفيديو والصوت…
+pp_extracting_audio = جارٍ استخراج الصوت…
+pp_embedding_thumbnail = جارٍ تضمين الصورة المصغرة…
+pp_converting_thumbnail = جارٍ تحويل الصورة المصغرة…
+pp_sponsorblock = جارٍ جلب مقاطع SponsorBlock…
+pp_modifying_chapters = جارٍ إزالة المقاطع الإعلانية…
+pp_embedding_metadata = جارٍ تضمين البيانات الوصفية…
+pp_embedding_subtitles = جارٍ تضمين الترجمات…
+pp_remuxing = جارٍ إعادة التغليف…
+pp_recoding = جارٍ إعادة ترميز الفيديو…
+pp_splitting_chapters = جارٍ تقسيم الفصول…
+pp_moving_files = جارٍ نقل الملفات…
+pp_fixing_up = جارٍ إصلاح الملف…
+pp_other = جارٍ المعالجة ({ $postprocessor })…
+post_download_hooks = أوامر ما بعد التنزيل
+add_hook = إضافة أمر
+post_download_hooks_help = أوامر تُنفّذ بعد تنزيل كل فيديو، ويُستبدل {"{"}filepath{"}"} و {"{"}title{"}"} و {"{"}url{"}"} و {"{"}preset{"}"} بقيم الفيديو
+running_hooks = جارٍ تنفيذ أوامر ما بعد التنزيل…
+hook_failed = فشل أمر ما بعد التنزيل: { $error }
+history = السجل
+clear_history = مسح السجل
+history_empty = لا توجد تنزيلات مكتملة بعد
+open_file = فتح الملف
+show_in_folder = عرض في المجلد
+open_folder = فتح المجلد
+retry = إعادة المحاولة
+download_failed = فشل التنزيل
+notification_files = { $count ->
+    [one] ملف واحد ({ $size })
+   *[other] { $count } ملفات ({ $size })
+}
+notifications = الإشعارات
+notify_download_finished = إشعار عند انتهاء التنزيل
+notify_download_failed = إشعار عند فشل التنزيل
+notify_hook_failed = إشعار عند فشل أمر ما بعد التنزيل
+notify_subscription_new_items = إشعار بالعناصر الجديدة في الاشتراكات
+retrying = إعادة المحاولة { $attempt }/{ $max } بعد { $seconds } ث
+retry_transient_errors = إعادة محاولة التنزيلات التي تفشل بسبب خطأ مؤقت
+retry_transient_errors_help = تعاد محاولة أخطاء الخادم وانتهاء المهلة والتقييد والأجزاء الفاشلة بتأخير متزايد، ولا تعاد محاولة المقاطع الخاصة أو غير المتوفرة أو المحجوبة جغرافيًا
+max_retries = عدد المحاولات
+retry_delay = التأخير الأول (بالثواني)
+error_geo_restricted = هذا المقطع غير متوفر في بلدك، جرّب وكيلًا من بلد آخر في إعدادات الشبكة
+error_login_required = هذا المقطع يتطلب تسجيل الدخول (قد يكون مقيدًا بالعمر)، استخدم ملفات تعريف الارتباط من متصفح سجلت الدخول فيه أو أضف تسجيل دخول للموقع في الإعدادات
+error_members_only = هذا المقطع لأعضاء القناة فقط، استخدم ملفات تعريف الارتباط من متصفح سجلت فيه الدخول بحساب عضو
+error_premiere_not_started = هذا العرض الأول أو البث المباشر لم يبدأ بعد، استخدم "التنزيل لاحقاً" أو سجّله كبث مباشر لانتظاره
+error_live_offline = القناة ليست في بث مباشر الآن، حاول مرة أخرى بعد بدء البث
+error_copyright_takedown = أزيل هذا المقطع بسبب مطالبة بحقوق الطبع والنشر
+error_unsupported_url = هذا الرابط غير مدعوم، تأكد من صحته أو حدّث yt-dlp
+error_ffmpeg_missing = لم يتم العثور على FFmpeg، ثبّته لدمج الصيغ وتحويل الملفات
+error_disk_full = لا توجد مساحة كافية على القرص، أفرغ بعض المساحة أو اختر مجلد تنزيل آخر
+error_rate_limited = الموقع يحدّ من الطلبات، انتظر قليلًا قبل المحاولة مرة أخرى أو استخدم ملفات تعريف الارتباط من متصفحك
+playlist_report = انتهت قائمة التشغيل: تم تنزيل { $succeeded }، وتخطي { $skipped }، وفشل { $failed }
+retry_failed_items = إعادة محاولة العناصر الفاشلة
+playlist_item_skipped = تم التخطي { $item }
+playlist_item_failed = فشل { $item }
+config_settings_overwritten = تم تعديل ملف الإعدادات، واستُبدلت هذه التغييرات غير المحفوظة بقيمه: { $settings }
@@ -23,3 +23,173 @@ cookies_file = Cookies file
 embed-thumbnail = Embed Thumbnail
 advanced-options = Advanced options
 playlist = Playlist
+config_parse_error = config.toml has errors, changes will not be saved until they are fixed: { $error }
+cookies = Cookies
+cookies_browser = From browser
+browser = Browser
+keyring = Keyring
+keyring_automatic = Automatic
+browser_profile = Profile (optional)
+browser_container = Container (optional)
+check = Check
+checking = Checking...
+cookies_check_summary = Extracted { $total } cookies, { $domain_cookies } for { $domain }
+cookies_check_logged_in = login cookies found
+cookies_check_not_logged_in = no login cookies found
+site_logins = Site logins
+add_login = Add login
+login_site = Site (e.g. vimeo.com)
+login_machine = yt-dlp extractor (e.g. vimeo)
+login_machine_missing = This login isn't used until its yt-dlp extractor is set, it's the extractor's netrc machine name e.g. watchnebula for nebula.tv
+login_username = Username
+login_password = Password
+preset = Preset
+no_preset = No preset
+network = Network
+override_network_for_preset = Override network settings for the "{ $preset }" preset
+proxy = Proxy
+source_address = Source address
+ip_version = IP version
+ip_version_any = Any
+socket_timeout = Timeout (seconds)
+retries = Retries
+fragment_retries = Fragment retries
+limit_rate = Rate limit (e.g. 2M)
+add_to_queue = Add to queue
+queue_paused = Queue paused by the bandwidth schedule
+queued = { $count } queued
+active_rule = Schedule: { $rule }
+bandwidth_schedule = Bandwidth schedule
+bandwidth_schedule_help = One rule per line: [days] HH:MM-HH:MM unlimited|pause|rate, e.g. "Sat,Sun 10:00-18:00 pause". The first matching rule applies
+add_rule = Add rule
+download_later = Download later
+cancel = Cancel
+start_at = Start at
+schedule = Schedule
+stop = Stop
+queue = Queue
+downloading_now = Downloading now
+queued_downloads = Queued
+scheduled_downloads = Scheduled
+queue_empty = No downloads in the queue
+subscriptions = Subscriptions
+subscription_url = Channel or playlist URL
+subscribe = Subscribe
+skip_existing_entries = Only download new uploads
+subscriptions_help = New subscriptions use the current download type, preset and destination path. For YouTube channels use the videos tab URL, e.g. https://www.youtube.com/@channel/videos
+sync_interval = Check every (minutes)
+subscription_status = Last checked: { $last_checked }, { $count } new
+never_checked = Not checked yet
+subscription_new_items = { $count } new items from { $url }
+livestream = Livestream
+live_from_start = Record from the start
+wait_for_video = Check for the stream every (seconds)
+max_recording_duration = Stop recording after (minutes)
+waiting_for_stream = Waiting for the stream to start...
+finishing_recording = Finishing the recording...
+recording_saved = Recording saved!
+download_sections = Sections
+chapters_regex = Chapters (name or regex)
+force_keyframes_at_cuts = Precise cuts (slower)
+fetch_info = Fetch info
+fetching_info = Fetching info...
+embed_chapters = Embed chapters
+split_chapters = Split by chapters
+chapter_template = Chapter file name template
+embed_metadata = Embed metadata
+fill_from_video = Fill from video
+tag_artist = Artist
+tag_album = Album
+tag_title = Title
+tag_track = Track (e.g. 3/12)
+tag_year = Year
+tag_genre = Genre
+write_thumbnail = Save thumbnail
+write_all_thumbnails = Save all thumbnails
+thumbnail_original_format = Original format
+square_thumbnail = Square cover
+thumbnail_embed_unsupported = Thumbnails can't be embedded in this format (WAV, AAC, WEBM, AVI and FLV aren't supported)
+format_selector = Format (-f)
+format_sort = Sort (-S)
+test_format = Test
+testing_format = Resolving the format...
+format_resolved = Selected: { $formats }
+format_test_needs_info = Fetch the video info first to test the format against it
+codec_any = Any
+video_codec = Video codec
+audio_codec = Audio codec
+max_fps = Max FPS
+dynamic_range = Dynamic range
+resolution_best = Best available
+strict_resolution = Exact resolution only
+audio_only_fallback = Audio only if there's no video
+download_finished_resolution = Download has finished! ({ $resolution })
+bitrate = Bitrate
+bitrate_variable = Variable (quality)
+sample_rate = Sample rate
+channels = Channels
+audio_original = Original
+audio_mono = Mono
+audio_stereo = Stereo
+audio_format_best = Original (no re-encode)
+recode_warning = This format needs the video to be re-encoded, which is much slower than downloading
+normalize_loudness = Normalize loudness (EBU R128)
+loudness_target = Target LUFS
+true_peak = True peak (dBTP)
+normalizing = Normalizing…
+pp_merging = Merging video and audio…
+pp_extracting_audio = Extracting audio…
+pp_embedding_thumbnail = Embedding thumbnail…
+pp_converting_thumbnail = Converting thumbnail…
+pp_sponsorblock = Fetching SponsorBlock segments…
+pp_modifying_chapters = Removing sponsor segments…
+pp_embedding_metadata = Embedding metadata…
+pp_embedding_subtitles = Embedding subtitles…
+pp_remuxing = Remuxing…
+pp_recoding = Re-encoding video…
+pp_splitting_chapters = Splitting chapters…
+pp_moving_files = Moving files…
+pp_fixing_up = Fixing up the file…
+pp_other = Processing ({ $postprocessor })…
+post_download_hooks = Post-download hooks
+add_hook = Add hook
+post_download_hooks_help = Shell commands run after each downloaded video, {"{"}filepath{"}"}, {"{"}title{"}"}, {"{"}url{"}"} and {"{"}preset{"}"} are replaced with the video's values
+running_hooks = Running post-download hooks…
+hook_failed = Post-download hook failed: { $error }
+history = History
+clear_history = Clear history
+history_empty = No finished downloads yet
+open_file = Open file
+show_in_folder = Show in folder
+open_folder = Open folder
+retry = Retry
+download_failed = Download failed
+notification_files = { $count ->
+    [one] 1 file ({ $size })
+   *[other] { $count } files ({ $size })
+}
+notifications = Notifications
+notify_download_finished = Notify when a download finishes
+notify_download_failed = Notify when a download fails
+notify_hook_failed = Notify when a post-download hook fails
+notify_subscription_new_items = Notify about new subscription items
+retrying = Retrying { $attempt }/{ $max } in { $seconds }s
+retry_transient_errors = Retry downloads that fail with a temporary error
+retry_transient_errors_help = Server errors, timeouts, throttling and failed fragments are retried with a growing delay, private, unavailable and geo-blocked videos are not
+max_retries = Retries
+retry_delay = First delay (seconds)
+error_geo_restricted = This video isn't available in your country, try a proxy from another country in the network settings
+error_login_required = This video needs a login (it may be age-restricted), use cookies from a browser where you're logged in or add a site login in the settings
+error_members_only = This video is for channel members only, use cookies from a browser logged into a member account
+error_premiere_not_started = This premiere or livestream hasn't started yet, use "Download later" or record it as a livestream to wait for it
+error_live_offline = The channel isn't live right now, try again once the stream has started
+error_copyright_takedown = This video was taken down because of a copyright claim
+error_unsupported_url = This link isn't supported, check that it's correct or update yt-dlp
+error_ffmpeg_missing = FFmpeg wasn't found, install it to merge formats and convert files
+error_disk_full = There is no space left on the disk, free some space or choose another download folder
+error_rate_limited = The site is limiting requests, wait a while before trying again or use cookies from your browser
+playlist_report = Playlist finished: { $succeeded } downloaded, { $skipped } skipped, { $failed } failed
+retry_failed_items = Retry failed items
+playlist_item_skipped = Skipped { $item }
+playlist_item_failed = Failed { $item }
+config_settings_overwritten = The config file was edited, these unsaved changes were replaced by its values: { $settings }
@@ -1,36 +1,64 @@
 use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{Local, TimeZone};
+use url::Url;
 
 use iced::widget::{
     column, container, pick_list, rich_text, row, scrollable, space, span, text, text_input,
 };
 use iced::{Alignment, Color, Event, Length, Padding, Point, Subscription, window};
 use iced_aw::Tabs;
-use url::Url;
+use serde::{Deserialize, Serialize};
 
 use crate::collapsible::collapsible;
+use crate::cookies::{Browser, CookiesSource, Keyring, check_browser_cookies};
+use crate::credentials::{self, Credential};
 use crate::error::DownloadError;
+use crate::history::{self, file_actions};
 use crate::i18n::{dir_row, is_rtl};
-use crate::media_options::{Options, playlist_options};
+use crate::media_options::{AudioBitrate, Loudness, Options};
+use crate::metadata::{TagField, Tags};
+use crate::network::{IpVersion, parse_number_input};
+use crate::notifications::{self, DownloadNotification, NotificationSettings};
+use crate::probe;
+use crate::redact::Secret;
+use crate::scheduled;
+use crate::scheduler::BandwidthRule;
+use crate::sections::parse_ranges;
 use crate::sponsorblock::SponsorBlockOption;
+use crate::subscriptions::ChannelSubscription;
 use crate::theme::{button, pick_list_menu_style, pick_list_style, tab_bar_style};
+use crate::thumbnail::{self, ThumbnailFormat};
 use crate::{Message, WindowPosition, YtGUI, choose_file, choose_folder};
 use crate::{checkbox::checkbox, fl};
-use notify_rust::Notification;
 
 pub const FONT_SIZE: f32 = 18.;
 
 pub const SPACING: f32 = 10.;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
 pub enum DownloadType {
     Video,
     Audio,
 }
 
+impl core::fmt::Display for DownloadType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadType::Video => f.write_str(&fl!("video")),
+            DownloadType::Audio => f.write_str(&fl!("audio")),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Tab {
     Video,
     Audio,
+    Queue,
+    Subscriptions,
+    History,
     Settings,
 }
 
@@ -38,17 +66,76 @@ impl YtGUI {
     pub fn update(&mut self, event: Message) -> iced::Task<Message> {
         match event {
             Message::InputChanged(input) => {
+                // the tags are for the video of the previous link
+                if input.trim() != self.download_link.trim() {
+                    self.tags = Tags::default();
+                }
+
                 self.download_link = input;
             }
             Message::SelectedResolution(resolution) => {
                 self.config.options.video_resolution = resolution;
             }
+            Message::SelectedVideoCodec(codec) => {
+                self.config.options.video_codec = codec;
+            }
+            Message::SelectedAudioCodec(codec) => {
+                self.config.options.audio_codec = codec;
+            }
+            Message::SelectedMaxFps(max_fps) => {
+                self.config.options.max_fps = max_fps;
+            }
+            Message::SelectedDynamicRange(dynamic_range) => {
+                self.config.options.dynamic_range = dynamic_range;
+            }
+            Message::ToggleStrictResolution(strict) => {
+                self.config.options.strict_resolution = strict;
+            }
+            Message::ToggleAudioOnlyFallback(fallback) => {
+                self.config.options.audio_only_fallback = fallback;
+            }
+            Message::SelectedAudioBitrate(bitrate) => {
+                self.config.options.audio_bitrate = bitrate;
+            }
+            Message::SelectedSampleRate(sample_rate) => {
+                self.config.options.sample_rate = sample_rate;
+            }
+            Message::SelectedAudioChannels(channels) => {
+                self.config.options.audio_channels = channels;
+            }
+            Message::ToggleNormalizeLoudness(normalize) => {
+                self.config.options.loudness.normalize = normalize;
+            }
+            Message::LoudnessTargetInput(input) => {
+                if let Ok(target) = Loudness::parse_target(&input) {
+                    self.config.options.loudness.target = target;
+                }
+                self.loudness_target_input = input;
+            }
+            Message::TruePeakInput(input) => {
+                if let Ok(true_peak) = Loudness::parse_true_peak(&input) {
+                    self.config.options.loudness.true_peak = true_peak;
+                }
+                self.true_peak_input = input;
+            }
             Message::TogglePlaylist(is_playlist) => {
                 self.is_playlist = is_playlist;
             }
             Message::ToggleThumbnail(get_thumbnail) => {
                 self.get_thumbnail = get_thumbnail;
             }
+            Message::ToggleWriteThumbnail(write) => {
+                self.config.thumbnails.write = write;
+            }
+            Message::ToggleWriteAllThumbnails(write_all) => {
+                self.config.thumbnails.write_all = write_all;
+            }
+            Message::SelectedThumbnailFormat(format) => {
+                self.config.thumbnails.format = format;
+            }
+            Message::ToggleSquareThumbnail(square) => {
+                self.config.thumbnails.square = square;
+            }
             Message::SelectedSponsorBlockOption(sponsorblock) => {
                 self.sponsorblock = sponsorblock;
             }
@@ -108,10 +195,10 @@ impl YtGUI {
                                 x: self.window_pos.x,
                                 y: self.window_pos.y,
                             });
-                            if let Err(e) = self.config.update_config_file() {
+                            if let Err(e) = self.save_config() {
                                 tracing::error!("Failed to update config file: {e}");
                             }
-                            return window::latest().and_then(|id| window::close(id));
+                            return window::latest().and_then(window::close);
                         }
                         window::Event::Resized(size) => {
                             self.window_width = size.width;
@@ -133,114 +220,256 @@ impl YtGUI {
                 }
             }
             Message::StartDownload(link) => {
-                self.config.download_folder = PathBuf::from(
-                    shellexpand::tilde(&self.config.download_folder.display().to_string())
-                        .to_string(),
-                );
+                if let Some(links) = self.validate_links(&link) {
+                    let job = self.new_job(links);
+                    self.enqueue_job(job);
+                }
+            }
+            Message::ToggleDownloadLater => {
+                self.show_download_later = !self.show_download_later;
+                self.start_time_error = None;
+            }
+            Message::StartTimeInput(input) => {
+                self.start_time_input = input;
+                self.start_time_error = None;
+            }
+            Message::ScheduleDownload(link) => {
+                let start_time =
+                    match scheduled::parse_start_time(&self.start_time_input, Local::now()) {
+                        Ok(start_time) => start_time,
+                        Err(e) => {
+                            self.start_time_error = Some(e.to_string());
+                            return iced::Task::none();
+                        }
+                    };
 
-                if !self.config.download_folder.exists() {
-                    self.progress = None;
-                    self.download_message = Some(Err(DownloadError::DownloadDir(
-                        self.config.download_folder.clone(),
-                    )));
-                    return iced::Task::none();
+                if let Some(links) = self.validate_links(&link) {
+                    let job = self.new_job(links);
+                    self.schedule_job(job, start_time);
+                    self.show_download_later = false;
+                    self.start_time_input.clear();
+                }
+            }
+            Message::CancelScheduledJob(index) => {
+                if index < self.scheduled_jobs.len() {
+                    self.scheduled_jobs.remove(index);
+                    self.save_scheduled_jobs();
                 }
+            }
+            Message::RemoveQueuedJob(index) => {
+                self.queue.remove(index);
+            }
+            Message::ScheduledJobsTick => self.start_due_jobs(),
+            Message::SubscriptionUrlInput(url) => {
+                self.subscription_url = url;
+            }
+            Message::ToggleSkipExisting(skip_existing) => {
+                self.skip_existing = skip_existing;
+            }
+            Message::AddSubscription => {
+                let url = self.subscription_url.trim().to_string();
 
-                let mut args: Vec<&str> = Vec::new();
+                if Url::parse(&url).is_err() {
+                    self.subscription_errors
+                        .insert(url, DownloadError::InvalidURL(1).to_string());
+                    return iced::Task::none();
+                }
 
-                let mut links_num = 0;
+                if self
+                    .config
+                    .subscriptions
+                    .iter()
+                    .any(|subscription| subscription.url == url)
+                {
+                    return iced::Task::none();
+                }
 
-                for (i, link) in link.trim().split(' ').enumerate() {
-                    if Url::parse(link).is_err() {
-                        self.progress = None;
-                        self.download_message = Some(Err(DownloadError::InvalidURL(i + 1)));
-                        return iced::Task::none();
-                    }
+                self.subscription_errors.clear();
+                self.subscription_url.clear();
+                self.config.subscriptions.push(ChannelSubscription {
+                    url,
+                    download_type: self.download_type,
+                    preset: self.config.active_preset.clone(),
+                    download_folder: self.config.download_folder.clone(),
+                    skip_existing: self.skip_existing,
+                    last_checked: None,
+                    new_items: 0,
+                });
+                let _ = self.save_config();
 
-                    self.config
-                        .update_config_file()
-                        .expect("update config file");
+                return self.sync_subscription(self.config.subscriptions.len() - 1);
+            }
+            Message::RemoveSubscription(index) => {
+                if index < self.config.subscriptions.len() {
+                    let subscription = self.config.subscriptions.remove(index);
+                    self.subscription_errors.remove(&subscription.url);
+                    let _ = self.save_config();
+                }
+            }
+            Message::SelectedSubscriptionType(index, download_type) => {
+                if let Some(subscription) = self.config.subscriptions.get_mut(index) {
+                    subscription.download_type = download_type;
+                }
+            }
+            Message::SelectedSubscriptionPreset(index, preset) => {
+                if let Some(subscription) = self.config.subscriptions.get_mut(index) {
+                    subscription.preset = Some(preset);
+                }
+            }
+            Message::SubscriptionFolderInput(index, folder) => {
+                if let Some(subscription) = self.config.subscriptions.get_mut(index) {
+                    subscription.download_folder = PathBuf::from(folder);
+                }
+            }
+            Message::SubscriptionIntervalInput(input) => {
+                if let Some(interval) = parse_number_input(&input) {
+                    self.config.subscription_sync_interval = interval.unwrap_or_default();
+                }
+            }
+            Message::SyncSubscription(index) => return self.sync_subscription(index),
+            Message::SyncSubscriptions => return self.sync_due_subscriptions(),
+            Message::SubscriptionSynced(url, res) => self.subscription_synced(url, res),
+            Message::StopDownload => {
+                if self.stop_recording() {
+                    return iced::Task::none();
+                }
 
-                    if link.is_empty() {
-                        self.progress = None;
-                        self.download_message = Some(Err(DownloadError::NoDownloadURL));
-                        return iced::Task::none();
+                self.command.kill();
+                self.recording = None;
+                self.current_job = None;
+                self.pending_retry = None;
+                self.playlist_report = None;
+                let _ = self.progress.take();
+                let _ = self.download_message.take();
+                self.start_next_job();
+            }
+            Message::DownloadProcessExited(pid, success) => {
+                // the process could be one that was already stopped
+                if self.command.id() == Some(pid) {
+                    if self
+                        .recording
+                        .as_ref()
+                        .is_some_and(|recording| recording.stopping)
+                    {
+                        self.end_download(Some(Ok(fl!("recording_saved"))));
+                    } else if success {
+                        self.end_download(Some(Ok(String::from("Download has finished!"))));
+                    } else {
+                        self.end_download(Some(Err(DownloadError::Exited)));
                     }
-
-                    args.push(link);
-
-                    links_num = i + 1;
                 }
+            }
+            Message::ToggleLive(is_live) => {
+                self.is_live = is_live;
+            }
+            Message::ToggleLiveFromStart(live_from_start) => {
+                self.config.live.live_from_start = live_from_start;
+            }
+            Message::LiveWaitForVideoInput(input) => {
+                if let Some(wait_for_video) = parse_number_input(&input) {
+                    self.config.live.wait_for_video = wait_for_video;
+                }
+            }
+            Message::LiveMaxDurationInput(input) => {
+                if let Some(max_duration) = parse_number_input(&input) {
+                    self.config.live.max_duration = max_duration;
+                }
+            }
+            Message::LiveTick => self.update_recording(),
+            Message::ToggleEmbedChapters(embed) => {
+                self.config.chapters.embed = embed;
+            }
+            Message::ToggleSplitChapters(split) => {
+                self.config.chapters.split = split;
+            }
+            Message::ChapterTemplateInput(template) => {
+                self.config.chapters.template = template;
+            }
+            Message::ToggleEmbedMetadata(embed_metadata) => {
+                self.config.embed_metadata = embed_metadata;
+            }
+            Message::TagInput(field, value) => {
+                self.tags.set(field, value);
+            }
+            Message::SectionsInput(input) => {
+                self.sections_input = input;
+            }
+            Message::ChaptersInput(input) => {
+                self.chapters_input = input;
+            }
+            Message::ToggleForceKeyframes(force_keyframes) => {
+                self.force_keyframes = force_keyframes;
+            }
+            Message::FetchInfo => {
+                let url = self.download_link.trim().to_string();
 
-                match self.download_type {
-                    DownloadType::Video => {
-                        args.push("-S");
-
-                        args.push(self.config.options.video_resolution.options());
-
-                        // after downloading a video with a specific format
-                        // yt-dlp sometimes downloads the audio and video seprately
-                        // then merge them in a different format
-                        // this enforces the chosen format by the user
-                        args.push("--remux-video");
-
-                        args.push(self.config.options.video_format.options());
-
-                        if self.get_thumbnail {
-                            args.push("--embed-thumbnail")
-                        }
+                if Url::parse(&url).is_err() || self.is_fetching_info {
+                    return iced::Task::none();
+                }
 
-                        tracing::debug!("{args:#?}");
-                    }
-                    DownloadType::Audio => {
-                        // Audio tab
+                self.is_fetching_info = true;
+                self.media_info = None;
 
-                        // Extract audio from Youtube video
-                        args.push("-x");
+                let mut args = self.cookies_args();
+                args.extend(
+                    self.config
+                        .network_options(self.config.active_preset.as_deref())
+                        .options(),
+                );
 
-                        args.push("--audio-format");
-                        args.push(self.config.options.audio_format.options());
+                return iced::Task::perform(
+                    probe::fetch_info(self.config.bin_path.clone(), url.clone(), args),
+                    move |res| Message::InfoFetched(url.clone(), res),
+                );
+            }
+            Message::InfoFetched(url, res) => {
+                self.is_fetching_info = false;
 
-                        args.push("--audio-quality");
-                        args.push(self.config.options.audio_quality.options());
+                if let Err(e) = &res {
+                    tracing::error!("failed to fetch info of {url}: {e}");
+                }
 
-                        if self.get_thumbnail {
-                            args.push("--embed-thumbnail")
-                        }
-                    }
+                if let Ok(info) = &res {
+                    self.tags = Tags::from_info(info);
                 }
 
-                if let Some(cookies_file) = &self.config.cookies_file {
-                    args.push("--cookies");
-                    args.push(cookies_file.to_str().unwrap());
+                self.media_info_url = url;
+                self.media_info = Some(res);
+                self.format_test = None;
+            }
+            Message::FormatSelectorInput(selector) => {
+                self.config.custom_format.selector = selector;
+                self.format_test = None;
+            }
+            Message::FormatSortInput(sort) => {
+                self.config.custom_format.sort = sort;
+                self.format_test = None;
+            }
+            Message::TestFormat => {
+                if let Err(e) = self.config.custom_format.validate() {
+                    self.format_test = Some(Err(e.to_string()));
+                    return iced::Task::none();
                 }
 
-                let playlist_options =
-                    playlist_options(self.is_playlist, self.config.download_folder.clone());
+                // the formats are resolved against the saved info of the fetched video
+                if !matches!(self.media_info, Some(Ok(_))) {
+                    self.format_test = Some(Err(fl!("format_test_needs_info")));
+                    return iced::Task::none();
+                }
 
-                args.append(&mut playlist_options.iter().map(|s| &**s).collect());
+                self.is_testing_format = true;
 
-                match self.sponsorblock {
-                    SponsorBlockOption::Disabled => {}
-                    SponsorBlockOption::Remove => {
-                        args.push("--sponsorblock-remove=default");
-                    }
-                    SponsorBlockOption::Mark => {
-                        args.push("--sponsorblock-mark=default");
-                    }
-                }
+                // the same -f and -S as the download
+                let format_args = self.config.custom_format.options(&self.config.options);
 
-                self.download_message = self.command.start(
-                    args,
-                    self.config.bin_path.clone(),
-                    self.sender.clone(),
-                    links_num,
+                return iced::Task::perform(
+                    probe::resolve_format(self.config.bin_path.clone(), format_args),
+                    Message::FormatTested,
                 );
             }
-            Message::StopDownload => {
-                self.command.kill();
-                let _ = self.progress.take();
-                let _ = self.download_message.take();
+            Message::FormatTested(res) => {
+                self.is_testing_format = false;
+                self.format_test = Some(res.map_err(|e| e.to_string()));
             }
             Message::ToggleSaveWindowPosition(save_window_position) => {
                 self.config.save_window_position = save_window_position;
@@ -287,6 +516,181 @@ impl YtGUI {
 
                 self.config.cookies_file = Some(path);
             }
+            Message::SelectedCookiesSource(source) => {
+                self.config.cookies_source = source;
+            }
+            Message::SelectedCookiesBrowser(browser) => {
+                self.config.browser_cookies.browser = browser;
+                self.cookies_check = None;
+            }
+            Message::SelectedCookiesKeyring(keyring) => {
+                self.config.browser_cookies.keyring = keyring;
+                self.cookies_check = None;
+            }
+            Message::CookiesProfileInput(profile) => {
+                self.config.browser_cookies.profile = profile;
+                self.cookies_check = None;
+            }
+            Message::CookiesContainerInput(container) => {
+                self.config.browser_cookies.container = container;
+                self.cookies_check = None;
+            }
+            Message::CheckBrowserCookies => {
+                if !self.is_checking_cookies {
+                    self.is_checking_cookies = true;
+                    self.cookies_check = None;
+
+                    return iced::Task::perform(
+                        check_browser_cookies(
+                            self.config.bin_path.clone(),
+                            self.config.browser_cookies.clone(),
+                            self.download_link.clone(),
+                        ),
+                        Message::BrowserCookiesChecked,
+                    );
+                }
+            }
+            Message::BrowserCookiesChecked(res) => {
+                if let Err(e) = &res {
+                    tracing::warn!("browser cookies check failed: {e}");
+                }
+                self.cookies_check = Some(res);
+                self.is_checking_cookies = false;
+            }
+            Message::AddCredential => {
+                self.config.credentials.push(Credential::default());
+            }
+            Message::RemoveCredential(index) => {
+                if index < self.config.credentials.len() {
+                    self.config.credentials.remove(index);
+                    self.update_netrc();
+                }
+            }
+            Message::CredentialDomainInput(index, domain) => {
+                if let Some(credential) = self.config.credentials.get_mut(index) {
+                    credential.domain = domain;
+                    self.update_netrc();
+                }
+            }
+            Message::CredentialMachineInput(index, machine) => {
+                if let Some(credential) = self.config.credentials.get_mut(index) {
+                    credential.machine = machine;
+                }
+            }
+            Message::CredentialUsernameInput(index, username) => {
+                if let Some(credential) = self.config.credentials.get_mut(index) {
+                    credential.username = username;
+                    self.update_netrc();
+                }
+            }
+            Message::CredentialPasswordInput(index, password) => {
+                if let Some(credential) = self.config.credentials.get_mut(index) {
+                    credential.password = password;
+                    self.update_netrc();
+                }
+            }
+            Message::SelectedPreset(name) => {
+                if let Some(preset) = self.config.presets.iter().find(|p| p.name == name) {
+                    self.config.options = preset.options;
+                    self.config.active_preset = Some(name);
+                }
+            }
+            Message::ToggleNetworkOverride(enabled) => {
+                let network = self.config.network.clone();
+                if let Some(preset) = self.config.active_preset_mut() {
+                    preset.network = enabled.then_some(network);
+                }
+            }
+            Message::NetworkProxyInput(proxy) => {
+                self.config.network_options_mut().proxy = proxy;
+            }
+            Message::NetworkSourceAddressInput(source_address) => {
+                self.config.network_options_mut().source_address = source_address;
+            }
+            Message::SelectedIpVersion(ip_version) => {
+                self.config.network_options_mut().ip_version = ip_version;
+            }
+            Message::NetworkSocketTimeoutInput(input) => {
+                if let Some(socket_timeout) = parse_number_input(&input) {
+                    self.config.network_options_mut().socket_timeout = socket_timeout;
+                }
+            }
+            Message::NetworkRetriesInput(input) => {
+                if let Some(retries) = parse_number_input(&input) {
+                    self.config.network_options_mut().retries = retries;
+                }
+            }
+            Message::NetworkFragmentRetriesInput(input) => {
+                if let Some(fragment_retries) = parse_number_input(&input) {
+                    self.config.network_options_mut().fragment_retries = fragment_retries;
+                }
+            }
+            Message::NetworkLimitRateInput(limit_rate) => {
+                self.config.network_options_mut().limit_rate = limit_rate;
+            }
+            Message::AddBandwidthRule => {
+                self.config.bandwidth_schedule.push(String::new());
+            }
+            Message::RemoveBandwidthRule(index) => {
+                if index < self.config.bandwidth_schedule.len() {
+                    self.config.bandwidth_schedule.remove(index);
+                    self.update_bandwidth_schedule();
+                }
+            }
+            Message::BandwidthRuleInput(index, rule) => {
+                if let Some(current_rule) = self.config.bandwidth_schedule.get_mut(index) {
+                    *current_rule = rule;
+                    self.update_bandwidth_schedule();
+                }
+            }
+            Message::SchedulerTick => self.update_bandwidth_schedule(),
+            Message::OpenFile(path) => history::open_file(&path),
+            Message::ShowInFolder(path) => history::show_in_folder(&path),
+            Message::RetryDownload(job) => self.enqueue_job(*job),
+            Message::NotificationSettingsChanged(settings) => {
+                self.config.notifications = settings;
+            }
+            Message::RetryTick => self.retry_tick(),
+            Message::ToggleRetry(enabled) => {
+                self.config.retry.enabled = enabled;
+            }
+            Message::MaxRetriesInput(input) => {
+                if let Some(max_retries) = parse_number_input(&input) {
+                    self.config.retry.max_retries = max_retries.unwrap_or_default();
+                }
+            }
+            Message::RetryDelayInput(input) => {
+                if let Some(initial_delay) = parse_number_input(&input) {
+                    self.config.retry.initial_delay = initial_delay.unwrap_or_default();
+                }
+            }
+            Message::ClearHistory => {
+                self.history.clear();
+                self.save_history();
+            }
+            Message::AddHook => {
+                self.config.post_download_hooks.push(String::new());
+            }
+            Message::RemoveHook(index) => {
+                if index < self.config.post_download_hooks.len() {
+                    self.config.post_download_hooks.remove(index);
+                }
+            }
+            Message::HookInput(index, hook) => {
+                if let Some(current_hook) = self.config.post_download_hooks.get_mut(index) {
+                    *current_hook = hook;
+                }
+            }
+            Message::HookFinished(result) => {
+                self.running_hooks = self.running_hooks.saturating_sub(1);
+
+                if let Err(e) = result {
+                    if self.config.notifications.hook_failed {
+                        notifications::notify(&fl!("hook_failed", error = e.to_string()));
+                    }
+                    self.hook_errors.push(e.to_string());
+                }
+            }
             Message::UpdateCheck(res) => {
                 // TODO: logging
                 match res {
@@ -307,6 +711,8 @@ impl YtGUI {
             Message::ToggleAdvancedOptions => {
                 self.show_advanced_options = !self.show_advanced_options;
             }
+            Message::CheckConfigFile => self.reload_config_if_changed(),
+            Message::DismissOverwrittenSettings => self.overwritten_settings.clear(),
         }
 
         iced::Task::none()
@@ -332,11 +738,22 @@ impl YtGUI {
                 ])
                 .spacing(SPACING)
                 .align_y(Alignment::Center),
-                checkbox(self.get_thumbnail)
-                    .label(fl!("embed-thumbnail"))
-                    .on_toggle(Message::ToggleThumbnail),
+                self.thumbnail_options(),
+                self.preset_options(),
             ];
 
+            if self.is_live {
+                options = options.push(self.live_options());
+            }
+
+            options = options
+                .push(self.chapter_options())
+                .push(self.section_options());
+
+            if self.download_type == DownloadType::Video {
+                options = options.push(self.format_options());
+            }
+
             if is_rtl() {
                 options = options.align_x(Alignment::End);
             }
@@ -371,6 +788,58 @@ impl YtGUI {
             .align_y(iced::Alignment::Center)
         };
 
+        let download_button = || {
+            let label = if self.is_downloading() || self.queue_paused {
+                fl!("add_to_queue")
+            } else {
+                fl!("download")
+            };
+
+            let download_later_label = if self.show_download_later {
+                fl!("cancel")
+            } else {
+                fl!("download_later")
+            };
+
+            let mut buttons = column![
+                dir_row(vec![
+                    button(text(label))
+                        .on_press(Message::StartDownload(self.download_link.clone()))
+                        .into(),
+                    button(text(download_later_label))
+                        .on_press(Message::ToggleDownloadLater)
+                        .into(),
+                ])
+                .spacing(SPACING)
+            ]
+            .spacing(SPACING)
+            .align_x(Alignment::Center);
+
+            if self.show_download_later {
+                buttons = buttons.push(
+                    dir_row(vec![
+                        text(format!("{}:", fl!("start_at"))).into(),
+                        text_input("YYYY-MM-DD HH:MM, HH:MM, +2h", &self.start_time_input)
+                            .on_input(Message::StartTimeInput)
+                            .on_submit(Message::ScheduleDownload(self.download_link.clone()))
+                            .width(Length::Fixed(260.))
+                            .into(),
+                        button(text(fl!("schedule")))
+                            .on_press(Message::ScheduleDownload(self.download_link.clone()))
+                            .into(),
+                    ])
+                    .spacing(SPACING)
+                    .align_y(Alignment::Center),
+                );
+            }
+
+            buttons.push(
+                self.start_time_error
+                    .as_ref()
+                    .map(|e| text(e.as_str()).style(text::danger)),
+            )
+        };
+
         let video_tab = column![
             row![if let Some(download_message) = &self.download_message {
                 self.show_download_progress(download_message)
@@ -382,23 +851,36 @@ impl YtGUI {
                         Options::video_formats(self.config.options.video_format).into()
                     ])
                     .padding(12),
+                    self.config
+                        .options
+                        .video_format
+                        .needs_recode()
+                        .then(|| text(fl!("recode_warning")).style(text::warning))
+                        .map(|warning| row![warning]
+                            .padding(Padding::ZERO.horizontal(12).bottom(12))),
+                    Options::codec_preferences(self.config.options)
+                        .padding(Padding::ZERO.horizontal(12).bottom(12)),
+                    dir_row(vec![
+                        checkbox(self.config.options.strict_resolution)
+                            .label(fl!("strict_resolution"))
+                            .on_toggle(Message::ToggleStrictResolution)
+                            .into(),
+                        checkbox(self.config.options.audio_only_fallback)
+                            .label(fl!("audio_only_fallback"))
+                            .on_toggle(Message::ToggleAudioOnlyFallback)
+                            .into(),
+                    ])
+                    .spacing(SPACING * 2.)
+                    .padding(Padding::ZERO.horizontal(12).bottom(12)),
                     advanced_options(),
                 ]
                 .width(Length::Fill)
             }],
-            column![
-                download_path(),
-                row![if !self.command.is_running() {
-                    button(text(fl!("download")))
-                        .on_press(Message::StartDownload(self.download_link.clone()))
-                } else {
-                    button(text(fl!("download")))
-                }]
-            ]
-            .width(Length::Fill)
-            .align_x(iced::Alignment::Center)
-            .spacing(20)
-            .padding(Padding::ZERO.top(20).horizontal(20))
+            column![download_path(), download_button()]
+                .width(Length::Fill)
+                .align_x(iced::Alignment::Center)
+                .spacing(20)
+                .padding(Padding::ZERO.top(20).horizontal(20))
         ];
 
         let audio_tab = column![
@@ -407,27 +889,31 @@ impl YtGUI {
             } else {
                 column![
                     dir_row(vec![
-                        Options::audio_qualities(self.config.options.audio_quality).into(),
+                        // the VBR quality is replaced by a constant bitrate, and lossless formats have neither
+                        if self.config.options.audio_format.is_lossless()
+                            || !self.config.options.audio_format.reencodes()
+                            || self.config.options.audio_bitrate != AudioBitrate::Vbr
+                        {
+                            space::horizontal().into()
+                        } else {
+                            Options::audio_qualities(self.config.options.audio_quality).into()
+                        },
                         space::horizontal().into(),
                         Options::audio_formats(self.config.options.audio_format).into(),
                     ])
                     .padding(12),
+                    Options::audio_encoding(self.config.options)
+                        .padding(Padding::ZERO.horizontal(12).bottom(12)),
+                    self.loudness_options(),
+                    self.tag_form(),
                     advanced_options(),
                 ]
             }],
-            column![
-                download_path(),
-                row![if !self.command.is_running() {
-                    button(text(fl!("download")))
-                        .on_press(Message::StartDownload(self.download_link.clone()))
-                } else {
-                    button(text(fl!("download")))
-                }]
-            ]
-            .width(Length::Fill)
-            .align_x(iced::Alignment::Center)
-            .spacing(20)
-            .padding(Padding::ZERO.top(20).horizontal(20))
+            column![download_path(), download_button()]
+                .width(Length::Fill)
+                .align_x(iced::Alignment::Center)
+                .spacing(20)
+                .padding(Padding::ZERO.top(20).horizontal(20))
         ];
 
         let mut settings_tab = column![
@@ -456,24 +942,25 @@ impl YtGUI {
             .spacing(SPACING)
             .align_y(iced::Alignment::Center),
             dir_row(vec![
-                text(format!("{}:", fl!("cookies_file"))).into(),
-                text_input(
-                    "",
-                    &self
-                        .config
-                        .cookies_file
-                        .clone()
-                        .unwrap_or("".into())
-                        .to_string_lossy()
+                text(format!("{}:", fl!("cookies"))).into(),
+                pick_list(
+                    vec![CookiesSource::File, CookiesSource::Browser],
+                    Some(self.config.cookies_source),
+                    Message::SelectedCookiesSource
                 )
-                .on_input(Message::SelectCookiesFileTextInput)
+                .style(pick_list_style)
+                .menu_style(pick_list_menu_style)
                 .into(),
-                button(text(fl!("browse")))
-                    .on_press(Message::SelectCookiesFile)
-                    .into(),
             ])
             .spacing(SPACING)
             .align_y(iced::Alignment::Center),
+            self.cookies_settings(),
+            self.credentials_settings(),
+            self.network_settings(),
+            self.bandwidth_schedule_settings(),
+            self.hooks_settings(),
+            self.retry_settings(),
+            self.notification_settings(),
         ]
         .width(Length::Fill)
         .spacing(20)
@@ -490,16 +977,46 @@ impl YtGUI {
                     iced_aw::TabLabel::Text(fl!("settings")),
                     scrollable(settings_tab),
                 )
+                .push(
+                    Tab::History,
+                    iced_aw::TabLabel::Text(fl!("history")),
+                    scrollable(self.history_tab()),
+                )
+                .push(
+                    Tab::Subscriptions,
+                    iced_aw::TabLabel::Text(fl!("subscriptions")),
+                    scrollable(self.subscriptions_tab()),
+                )
+                .push(
+                    Tab::Queue,
+                    iced_aw::TabLabel::Text(fl!("queue")),
+                    scrollable(self.queue_tab()),
+                )
                 .push(Tab::Audio, iced_aw::TabLabel::Text(fl!("audio")), audio_tab)
                 .push(Tab::Video, iced_aw::TabLabel::Text(fl!("video")), video_tab)
         } else {
             Tabs::new(Message::SelectTab)
                 .push(Tab::Video, iced_aw::TabLabel::Text(fl!("video")), video_tab)
                 .push(Tab::Audio, iced_aw::TabLabel::Text(fl!("audio")), audio_tab)
+                .push(
+                    Tab::Queue,
+                    iced_aw::TabLabel::Text(fl!("queue")),
+                    scrollable(self.queue_tab()),
+                )
+                .push(
+                    Tab::Subscriptions,
+                    iced_aw::TabLabel::Text(fl!("subscriptions")),
+                    scrollable(self.subscriptions_tab()),
+                )
+                .push(
+                    Tab::History,
+                    iced_aw::TabLabel::Text(fl!("history")),
+                    scrollable(self.history_tab()),
+                )
                 .push(
                     Tab::Settings,
                     iced_aw::TabLabel::Text(fl!("settings")),
-                    settings_tab,
+                    scrollable(settings_tab),
                 )
         }
         .set_active_tab(&self.active_tab)
@@ -526,9 +1043,30 @@ impl YtGUI {
                     .label(fl!("playlist"))
                     .on_toggle(Message::TogglePlaylist)
                     .into(),
+                checkbox(self.is_live)
+                    .label(fl!("livestream"))
+                    .on_toggle(Message::ToggleLive)
+                    .into(),
             ])
             .spacing(7)
             .align_y(iced::Alignment::Center),
+            self.config_error.as_ref().map(|e| row![
+                text(fl!("config_parse_error", error = e.as_str())).style(text::danger)
+            ]),
+            (!self.overwritten_settings.is_empty()).then(|| dir_row(vec![
+                text(fl!(
+                    "config_settings_overwritten",
+                    settings = self.overwritten_settings.join(", ")
+                ))
+                .style(text::warning)
+                .width(Length::Fill)
+                .into(),
+                button(text("X"))
+                    .on_press(Message::DismissOverwrittenSettings)
+                    .into(),
+            ])
+            .spacing(SPACING)
+            .align_y(Alignment::Center)),
             tabs,
             self.new_version.as_ref().map(|new_version| row![
                 column![
@@ -559,23 +1097,959 @@ impl YtGUI {
             .into()
     }
 
-    pub fn subscription(&self) -> Subscription<Message> {
-        iced::event::listen().map(Message::IcedEvent)
-    }
+    fn cookies_settings(&self) -> iced::widget::Column<'_, Message> {
+        match self.config.cookies_source {
+            CookiesSource::File => column![
+                dir_row(vec![
+                    text(format!("{}:", fl!("cookies_file"))).into(),
+                    text_input(
+                        "",
+                        &self
+                            .config
+                            .cookies_file
+                            .clone()
+                            .unwrap_or("".into())
+                            .to_string_lossy()
+                    )
+                    .on_input(Message::SelectCookiesFileTextInput)
+                    .into(),
+                    button(text(fl!("browse")))
+                        .on_press(Message::SelectCookiesFile)
+                        .into(),
+                ])
+                .spacing(SPACING)
+                .align_y(iced::Alignment::Center)
+            ],
+            CookiesSource::Browser => {
+                let browser_cookies = &self.config.browser_cookies;
 
-    pub fn end_download(&mut self, download_message: Option<Result<String, DownloadError>>) {
-        self.command.kill();
-        self.progress = None;
-        self.download_message = download_message;
-        self.log_download();
-        match &self.download_message {
-            Some(Ok(download_message)) => {
-                let _ = Notification::new().summary(download_message).show();
+                let mut check_button = button(text(fl!("check")));
+                if !self.is_checking_cookies {
+                    check_button = check_button.on_press(Message::CheckBrowserCookies);
+                }
+
+                let check_result = match &self.cookies_check {
+                    Some(Ok(check)) => Some(text(check.to_string())),
+                    Some(Err(e)) => Some(text(e.to_string()).style(text::danger)),
+                    None if self.is_checking_cookies => Some(text(fl!("checking"))),
+                    None => None,
+                };
+
+                column![
+                    dir_row(vec![
+                        text(format!("{}:", fl!("browser"))).into(),
+                        pick_list(
+                            Browser::ALL,
+                            Some(browser_cookies.browser),
+                            Message::SelectedCookiesBrowser
+                        )
+                        .style(pick_list_style)
+                        .menu_style(pick_list_menu_style)
+                        .into(),
+                        text(format!("{}:", fl!("keyring"))).into(),
+                        pick_list(
+                            Keyring::ALL,
+                            Some(browser_cookies.keyring),
+                            Message::SelectedCookiesKeyring
+                        )
+                        .style(pick_list_style)
+                        .menu_style(pick_list_menu_style)
+                        .into(),
+                    ])
+                    .spacing(SPACING)
+                    .align_y(iced::Alignment::Center),
+                    dir_row(vec![
+                        text_input(&fl!("browser_profile"), &browser_cookies.profile)
+                            .on_input(Message::CookiesProfileInput)
+                            .into(),
+                        text_input(&fl!("browser_container"), &browser_cookies.container)
+                            .on_input(Message::CookiesContainerInput)
+                            .into(),
+                        check_button.into(),
+                    ])
+                    .spacing(SPACING)
+                    .align_y(iced::Alignment::Center),
+                    check_result,
+                ]
+                .spacing(SPACING)
             }
-            Some(Err(e)) => {
-                let _ = Notification::new().summary(&e.to_string()).show();
+        }
+    }
+
+    fn credentials_settings(&self) -> iced::widget::Column<'_, Message> {
+        let mut credentials = column![
+            dir_row(vec![
+                text(format!("{}:", fl!("site_logins"))).into(),
+                button(text(fl!("add_login")))
+                    .on_press(Message::AddCredential)
+                    .into(),
+            ])
+            .spacing(SPACING)
+            .align_y(iced::Alignment::Center)
+        ]
+        .spacing(SPACING);
+
+        for (i, credential) in self.config.credentials.iter().enumerate() {
+            credentials = credentials.push(
+                dir_row(vec![
+                    text_input(&fl!("login_site"), &credential.domain)
+                        .on_input(move |domain| Message::CredentialDomainInput(i, domain))
+                        .into(),
+                    text_input(&fl!("login_machine"), &credential.machine)
+                        .on_input(move |machine| Message::CredentialMachineInput(i, machine))
+                        .into(),
+                    text_input(&fl!("login_username"), &credential.username)
+                        .on_input(move |username| Message::CredentialUsernameInput(i, username))
+                        .into(),
+                    text_input(&fl!("login_password"), credential.password.expose())
+                        .secure(true)
+                        .on_input(move |password| {
+                            Message::CredentialPasswordInput(i, Secret::new(password))
+                        })
+                        .into(),
+                    button(text("X"))
+                        .on_press(Message::RemoveCredential(i))
+                        .into(),
+                ])
+                .spacing(SPACING)
+                .align_y(iced::Alignment::Center),
+            );
+
+            // yt-dlp looks up the login by the machine name, not the site
+            if !credential.domain.trim().is_empty() && credential.machine.trim().is_empty() {
+                credentials =
+                    credentials.push(text(fl!("login_machine_missing")).style(text::warning));
+            }
+        }
+
+        credentials
+    }
+
+    fn preset_options(&self) -> iced::widget::Row<'_, Message> {
+        let presets: Vec<String> = self
+            .config
+            .presets
+            .iter()
+            .map(|preset| preset.name.clone())
+            .collect();
+
+        dir_row(vec![
+            text(format!("{}:", fl!("preset"))).into(),
+            pick_list(
+                presets,
+                self.config.active_preset.clone(),
+                Message::SelectedPreset,
+            )
+            .placeholder(fl!("no_preset"))
+            .style(pick_list_style)
+            .menu_style(pick_list_menu_style)
+            .into(),
+        ])
+        .spacing(SPACING)
+        .align_y(Alignment::Center)
+    }
+
+    fn network_settings(&self) -> iced::widget::Column<'_, Message> {
+        let network = self
+            .config
+            .network_options(self.config.active_preset.as_deref());
+
+        let mut settings = column![text(fl!("network")).size(FONT_SIZE)].spacing(SPACING);
+
+        if let Some(preset) = self.config.active_preset() {
+            settings = settings.push(
+                checkbox(preset.network.is_some())
+                    .label(fl!(
+                        "override_network_for_preset",
+                        preset = preset.name.as_str()
+                    ))
+                    .on_toggle(Message::ToggleNetworkOverride),
+            );
+        }
+
+        let number_input =
+            |placeholder: String, value: Option<u32>, on_input: fn(String) -> Message| {
+                text_input(
+                    &placeholder,
+                    &value.map(|v| v.to_string()).unwrap_or_default(),
+                )
+                .on_input(on_input)
+                .width(Length::Fixed(140.))
+            };
+
+        settings
+            .push(
+                dir_row(vec![
+                    text(format!("{}:", fl!("proxy"))).into(),
+                    text_input("socks5://127.0.0.1:1080", &network.proxy)
+                        .on_input(Message::NetworkProxyInput)
+                        .into(),
+                ])
+                .spacing(SPACING)
+                .align_y(Alignment::Center),
+            )
+            .push(
+                dir_row(vec![
+                    text(format!("{}:", fl!("source_address"))).into(),
+                    text_input("", &network.source_address)
+                        .on_input(Message::NetworkSourceAddressInput)
+                        .into(),
+                    text(format!("{}:", fl!("ip_version"))).into(),
+                    pick_list(
+                        [IpVersion::Any, IpVersion::V4, IpVersion::V6],
+                        Some(network.ip_version),
+                        Message::SelectedIpVersion,
+                    )
+                    .style(pick_list_style)
+                    .menu_style(pick_list_menu_style)
+                    .into(),
+                ])
+                .spacing(SPACING)
+                .align_y(Alignment::Center),
+            )
+            .push(
+                dir_row(vec![
+                    number_input(
+                        fl!("socket_timeout"),
+                        network.socket_timeout,
+                        Message::NetworkSocketTimeoutInput,
+                    )
+                    .into(),
+                    number_input(
+                        fl!("retries"),
+                        network.retries,
+                        Message::NetworkRetriesInput,
+                    )
+                    .into(),
+                    number_input(
+                        fl!("fragment_retries"),
+                        network.fragment_retries,
+                        Message::NetworkFragmentRetriesInput,
+                    )
+                    .into(),
+                    text_input(&fl!("limit_rate"), &network.limit_rate)
+                        .on_input(Message::NetworkLimitRateInput)
+                        .into(),
+                ])
+                .spacing(SPACING)
+                .align_y(Alignment::Center),
+            )
+    }
+
+    fn bandwidth_schedule_settings(&self) -> iced::widget::Column<'_, Message> {
+        let mut settings = column![
+            dir_row(vec![
+                text(fl!("bandwidth_schedule")).size(FONT_SIZE).into(),
+                space::horizontal().into(),
+                button(text(fl!("add_rule")))
+                    .on_press(Message::AddBandwidthRule)
+                    .into(),
+            ])
+            .align_y(Alignment::Center),
+            text(fl!("bandwidth_schedule_help")).size(14),
+        ]
+        .spacing(SPACING);
+
+        for (i, rule) in self.config.bandwidth_schedule.iter().enumerate() {
+            settings = settings.push(
+                dir_row(vec![
+                    text_input("Mon-Fri 09:00-18:00 2M", rule)
+                        .on_input(move |rule| Message::BandwidthRuleInput(i, rule))
+                        .into(),
+                    button(text("X"))
+                        .on_press(Message::RemoveBandwidthRule(i))
+                        .into(),
+                ])
+                .spacing(SPACING)
+                .align_y(Alignment::Center),
+            );
+
+            if let Err(e) = rule.parse::<BandwidthRule>() {
+                settings = settings.push(text(e.to_string()).style(text::danger));
+            }
+        }
+
+        settings
+    }
+
+    fn hooks_settings(&self) -> iced::widget::Column<'_, Message> {
+        let mut settings = column![
+            dir_row(vec![
+                text(fl!("post_download_hooks")).size(FONT_SIZE).into(),
+                space::horizontal().into(),
+                button(text(fl!("add_hook")))
+                    .on_press(Message::AddHook)
+                    .into(),
+            ])
+            .align_y(Alignment::Center),
+            text(fl!("post_download_hooks_help")).size(14),
+        ]
+        .spacing(SPACING);
+
+        for (i, hook) in self.config.post_download_hooks.iter().enumerate() {
+            settings = settings.push(
+                dir_row(vec![
+                    text_input("mv {filepath} ~/Media/", hook)
+                        .on_input(move |hook| Message::HookInput(i, hook))
+                        .into(),
+                    button(text("X")).on_press(Message::RemoveHook(i)).into(),
+                ])
+                .spacing(SPACING)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        settings
+    }
+
+    fn retry_settings(&self) -> iced::widget::Column<'_, Message> {
+        let retry = self.config.retry;
+
+        column![
+            checkbox(retry.enabled)
+                .label(fl!("retry_transient_errors"))
+                .on_toggle(Message::ToggleRetry),
+            text(fl!("retry_transient_errors_help")).size(14),
+            dir_row(vec![
+                text(format!("{}:", fl!("max_retries"))).into(),
+                text_input("5", &retry.max_retries.to_string())
+                    .on_input_maybe(retry.enabled.then_some(Message::MaxRetriesInput))
+                    .width(Length::Fixed(80.))
+                    .into(),
+                text(format!("{}:", fl!("retry_delay"))).into(),
+                text_input("15", &retry.initial_delay.to_string())
+                    .on_input_maybe(retry.enabled.then_some(Message::RetryDelayInput))
+                    .width(Length::Fixed(80.))
+                    .into(),
+            ])
+            .spacing(SPACING)
+            .align_y(Alignment::Center),
+        ]
+        .spacing(SPACING)
+    }
+
+    fn notification_settings(&self) -> iced::widget::Column<'_, Message> {
+        let settings = self.config.notifications;
+
+        column![
+            text(fl!("notifications")).size(FONT_SIZE),
+            checkbox(settings.download_finished)
+                .label(fl!("notify_download_finished"))
+                .on_toggle(move |download_finished| {
+                    Message::NotificationSettingsChanged(NotificationSettings {
+                        download_finished,
+                        ..settings
+                    })
+                }),
+            checkbox(settings.download_failed)
+                .label(fl!("notify_download_failed"))
+                .on_toggle(move |download_failed| {
+                    Message::NotificationSettingsChanged(NotificationSettings {
+                        download_failed,
+                        ..settings
+                    })
+                }),
+            checkbox(settings.hook_failed)
+                .label(fl!("notify_hook_failed"))
+                .on_toggle(move |hook_failed| {
+                    Message::NotificationSettingsChanged(NotificationSettings {
+                        hook_failed,
+                        ..settings
+                    })
+                }),
+            checkbox(settings.subscription_new_items)
+                .label(fl!("notify_subscription_new_items"))
+                .on_toggle(move |subscription_new_items| {
+                    Message::NotificationSettingsChanged(NotificationSettings {
+                        subscription_new_items,
+                        ..settings
+                    })
+                }),
+        ]
+        .spacing(SPACING)
+    }
+
+    fn history_tab(&self) -> iced::widget::Column<'_, Message> {
+        let mut history = column![
+            dir_row(vec![
+                space::horizontal().into(),
+                button(text(fl!("clear_history")))
+                    .on_press_maybe((!self.history.is_empty()).then_some(Message::ClearHistory))
+                    .into(),
+            ])
+            .align_y(Alignment::Center)
+        ]
+        .spacing(SPACING);
+
+        for entry in self.history.iter().rev() {
+            history = history.push(
+                dir_row(vec![
+                    text(entry.job.label()).width(Length::Fill).into(),
+                    text(entry.finish_time().format("%Y-%m-%d %H:%M").to_string()).into(),
+                ])
+                .spacing(SPACING)
+                .align_y(Alignment::Center),
+            );
+
+            if let Some(error) = &entry.error {
+                history = history.push(text(error.as_str()).size(14).style(text::danger));
+            }
+
+            for file in &entry.job.files {
+                history = history.push(file_actions(file));
+            }
+        }
+
+        if self.history.is_empty() {
+            history = history.push(text(fl!("history_empty")));
+        }
+
+        let history = history.width(Length::Fill).padding(20);
+
+        if is_rtl() {
+            history.align_x(Alignment::End)
+        } else {
+            history
+        }
+    }
+
+    fn queue_tab(&self) -> iced::widget::Column<'_, Message> {
+        let mut queue = column![].spacing(SPACING);
+
+        if let Some(job) = &self.current_job {
+            queue = queue
+                .push(text(fl!("downloading_now")).size(FONT_SIZE))
+                .push(
+                    dir_row(vec![
+                        text(job.label()).width(Length::Fill).into(),
+                        button(text(fl!("stop")))
+                            .on_press(Message::StopDownload)
+                            .into(),
+                    ])
+                    .spacing(SPACING)
+                    .align_y(Alignment::Center),
+                );
+        }
+
+        if !self.queue.is_empty() {
+            queue = queue.push(text(fl!("queued_downloads")).size(FONT_SIZE));
+        }
+
+        for (i, job) in self.queue.iter().enumerate() {
+            queue = queue.push(
+                dir_row(vec![
+                    text(job.label()).width(Length::Fill).into(),
+                    button(text("X"))
+                        .on_press(Message::RemoveQueuedJob(i))
+                        .into(),
+                ])
+                .spacing(SPACING)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        if !self.scheduled_jobs.is_empty() {
+            queue = queue.push(text(fl!("scheduled_downloads")).size(FONT_SIZE));
+        }
+
+        let now = Local::now();
+
+        for (i, scheduled) in self.scheduled_jobs.iter().enumerate() {
+            let countdown = scheduled::format_countdown(scheduled.start_at - now.timestamp());
+
+            queue = queue.push(
+                dir_row(vec![
+                    text(scheduled.job.label()).width(Length::Fill).into(),
+                    text(format!(
+                        "{} ({countdown})",
+                        scheduled.start_time().format("%Y-%m-%d %H:%M")
+                    ))
+                    .into(),
+                    button(text("X"))
+                        .on_press(Message::CancelScheduledJob(i))
+                        .into(),
+                ])
+                .spacing(SPACING)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        if self.current_job.is_none() && self.queue.is_empty() && self.scheduled_jobs.is_empty() {
+            queue = queue.push(text(fl!("queue_empty")));
+        }
+
+        let queue = queue.width(Length::Fill).padding(20);
+
+        if is_rtl() {
+            queue.align_x(Alignment::End)
+        } else {
+            queue
+        }
+    }
+
+    fn subscriptions_tab(&self) -> iced::widget::Column<'_, Message> {
+        let presets: Vec<String> = self
+            .config
+            .presets
+            .iter()
+            .map(|preset| preset.name.clone())
+            .collect();
+
+        let mut subscriptions = column![
+            dir_row(vec![
+                text_input(&fl!("subscription_url"), &self.subscription_url)
+                    .on_input(Message::SubscriptionUrlInput)
+                    .on_submit(Message::AddSubscription)
+                    .into(),
+                button(text(fl!("subscribe")))
+                    .on_press(Message::AddSubscription)
+                    .into(),
+            ])
+            .spacing(SPACING)
+            .align_y(Alignment::Center),
+            self.subscription_errors
+                .get(self.subscription_url.trim())
+                .map(|e| text(e.as_str()).style(text::danger)),
+            checkbox(self.skip_existing)
+                .label(fl!("skip_existing_entries"))
+                .on_toggle(Message::ToggleSkipExisting),
+            text(fl!("subscriptions_help")).size(14),
+            dir_row(vec![
+                text(format!("{}:", fl!("sync_interval"))).into(),
+                text_input("60", &self.config.subscription_sync_interval.to_string())
+                    .on_input(Message::SubscriptionIntervalInput)
+                    .width(Length::Fixed(80.))
+                    .into(),
+            ])
+            .spacing(SPACING)
+            .align_y(Alignment::Center),
+        ]
+        .spacing(SPACING);
+
+        for (i, subscription) in self.config.subscriptions.iter().enumerate() {
+            let status = if self.syncing_subscriptions.contains(&subscription.url) {
+                text(fl!("checking"))
+            } else if let Some(e) = self.subscription_errors.get(&subscription.url) {
+                text(e.as_str()).style(text::danger)
+            } else if let Some(last_checked) = subscription
+                .last_checked
+                .and_then(|last_checked| Local.timestamp_opt(last_checked, 0).single())
+            {
+                text(fl!(
+                    "subscription_status",
+                    last_checked = last_checked.format("%Y-%m-%d %H:%M").to_string(),
+                    count = subscription.new_items
+                ))
+            } else {
+                text(fl!("never_checked"))
+            };
+
+            subscriptions = subscriptions.push(
+                column![
+                    dir_row(vec![
+                        text(subscription.url.as_str()).width(Length::Fill).into(),
+                        button(text(fl!("check")))
+                            .on_press_maybe(
+                                (!self.syncing_subscriptions.contains(&subscription.url))
+                                    .then_some(Message::SyncSubscription(i)),
+                            )
+                            .into(),
+                        button(text("X"))
+                            .on_press(Message::RemoveSubscription(i))
+                            .into(),
+                    ])
+                    .spacing(SPACING)
+                    .align_y(Alignment::Center),
+                    dir_row(vec![
+                        pick_list(
+                            [DownloadType::Video, DownloadType::Audio],
+                            Some(subscription.download_type),
+                            move |download_type| {
+                                Message::SelectedSubscriptionType(i, download_type)
+                            },
+                        )
+                        .style(pick_list_style)
+                        .menu_style(pick_list_menu_style)
+                        .into(),
+                        pick_list(
+                            presets.clone(),
+                            subscription.preset.clone(),
+                            move |preset| { Message::SelectedSubscriptionPreset(i, preset) }
+                        )
+                        .placeholder(fl!("no_preset"))
+                        .style(pick_list_style)
+                        .menu_style(pick_list_menu_style)
+                        .into(),
+                        text_input(
+                            &fl!("download_path"),
+                            &subscription.download_folder.display().to_string()
+                        )
+                        .on_input(move |folder| Message::SubscriptionFolderInput(i, folder))
+                        .into(),
+                    ])
+                    .spacing(SPACING)
+                    .align_y(Alignment::Center),
+                    status,
+                ]
+                .spacing(5),
+            );
+        }
+
+        let subscriptions = subscriptions.width(Length::Fill).padding(20);
+
+        if is_rtl() {
+            subscriptions.align_x(Alignment::End)
+        } else {
+            subscriptions
+        }
+    }
+
+    fn tag_form(&self) -> iced::widget::Column<'_, Message> {
+        let mut form = column![
+            dir_row(vec![
+                checkbox(self.config.embed_metadata)
+                    .label(fl!("embed_metadata"))
+                    .on_toggle(Message::ToggleEmbedMetadata)
+                    .into(),
+                space::horizontal().into(),
+                button(text(fl!("fill_from_video")))
+                    .on_press_maybe((!self.is_fetching_info).then_some(Message::FetchInfo))
+                    .into(),
+            ])
+            .spacing(SPACING)
+            .align_y(Alignment::Center)
+        ]
+        .spacing(SPACING)
+        .padding([0, 12]);
+
+        if !self.config.embed_metadata {
+            return form;
+        }
+
+        let tag_input = |field: TagField, placeholder: String| {
+            text_input(&placeholder, self.tags.get(field))
+                .on_input(move |value| Message::TagInput(field, value))
+                .into()
+        };
+
+        form = form
+            .push(
+                dir_row(vec![
+                    tag_input(TagField::Artist, fl!("tag_artist")),
+                    tag_input(TagField::Album, fl!("tag_album")),
+                    tag_input(TagField::Title, fl!("tag_title")),
+                ])
+                .spacing(SPACING),
+            )
+            .push(
+                dir_row(vec![
+                    tag_input(TagField::Track, fl!("tag_track")),
+                    tag_input(TagField::Year, fl!("tag_year")),
+                    tag_input(TagField::Genre, fl!("tag_genre")),
+                ])
+                .spacing(SPACING),
+            );
+
+        form.push(
+            self.tags
+                .validate()
+                .err()
+                .map(|e| text(e.to_string()).style(text::danger)),
+        )
+    }
+
+    fn thumbnail_options(&self) -> iced::widget::Column<'_, Message> {
+        let thumbnails = &self.config.thumbnails;
+
+        let mut options = vec![
+            checkbox(self.get_thumbnail)
+                .label(fl!("embed-thumbnail"))
+                .on_toggle(Message::ToggleThumbnail)
+                .into(),
+            checkbox(thumbnails.write)
+                .label(fl!("write_thumbnail"))
+                .on_toggle(Message::ToggleWriteThumbnail)
+                .into(),
+            checkbox(thumbnails.write_all)
+                .label(fl!("write_all_thumbnails"))
+                .on_toggle(Message::ToggleWriteAllThumbnails)
+                .into(),
+            pick_list(
+                ThumbnailFormat::ALL,
+                Some(thumbnails.format),
+                Message::SelectedThumbnailFormat,
+            )
+            .style(pick_list_style)
+            .menu_style(pick_list_menu_style)
+            .into(),
+        ];
+
+        if self.download_type == DownloadType::Audio {
+            options.push(
+                checkbox(thumbnails.square)
+                    .label(fl!("square_thumbnail"))
+                    .on_toggle(Message::ToggleSquareThumbnail)
+                    .into(),
+            );
+        }
+
+        let warning = (self.get_thumbnail
+            && !thumbnail::supports_embedding(self.download_type, &self.config.options))
+        .then(|| text(fl!("thumbnail_embed_unsupported")).style(text::warning));
+
+        column![
+            dir_row(options).spacing(SPACING).align_y(Alignment::Center),
+            warning,
+        ]
+        .spacing(SPACING)
+    }
+
+    fn chapter_options(&self) -> iced::widget::Row<'_, Message> {
+        let chapters = &self.config.chapters;
+
+        let mut options = vec![
+            checkbox(chapters.embed)
+                .label(fl!("embed_chapters"))
+                .on_toggle(Message::ToggleEmbedChapters)
+                .into(),
+            checkbox(chapters.split)
+                .label(fl!("split_chapters"))
+                .on_toggle(Message::ToggleSplitChapters)
+                .into(),
+        ];
+
+        if chapters.split {
+            options.push(
+                text_input(&fl!("chapter_template"), &chapters.template)
+                    .on_input(Message::ChapterTemplateInput)
+                    .into(),
+            );
+        }
+
+        dir_row(options).spacing(SPACING).align_y(Alignment::Center)
+    }
+
+    fn section_options(&self) -> iced::widget::Column<'_, Message> {
+        let mut fetch_button = button(text(fl!("fetch_info")));
+        if !self.is_fetching_info {
+            fetch_button = fetch_button.on_press(Message::FetchInfo);
+        }
+
+        let info = match &self.media_info {
+            _ if self.is_fetching_info => Some(text(fl!("fetching_info"))),
+            Some(Ok(info)) => Some(text(info.to_string())),
+            Some(Err(e)) => Some(text(e.to_string()).style(text::danger)),
+            None => None,
+        };
+
+        column![
+            dir_row(vec![
+                text(format!("{}:", fl!("download_sections"))).into(),
+                text_input("1:30-2:45, 10:00-inf", &self.sections_input)
+                    .on_input(Message::SectionsInput)
+                    .into(),
+                text_input(&fl!("chapters_regex"), &self.chapters_input)
+                    .on_input(Message::ChaptersInput)
+                    .into(),
+                checkbox(self.force_keyframes)
+                    .label(fl!("force_keyframes_at_cuts"))
+                    .on_toggle(Message::ToggleForceKeyframes)
+                    .into(),
+            ])
+            .spacing(SPACING)
+            .align_y(Alignment::Center),
+            parse_ranges(&self.sections_input)
+                .err()
+                .map(|e| text(e.to_string()).style(text::danger)),
+            dir_row(vec![fetch_button.into(), info.into()])
+                .spacing(SPACING)
+                .align_y(Alignment::Center),
+        ]
+        .spacing(SPACING)
+    }
+
+    fn format_options(&self) -> iced::widget::Column<'_, Message> {
+        let custom_format = &self.config.custom_format;
+
+        let result = match &self.format_test {
+            _ if self.is_testing_format => Some(text(fl!("testing_format"))),
+            Some(Ok(format_ids)) => {
+                let info = self.media_info.as_ref().and_then(|info| info.as_ref().ok());
+
+                let formats = format_ids
+                    .iter()
+                    .map(|id| {
+                        info.and_then(|info| info.format(id))
+                            .map_or_else(|| id.clone(), ToString::to_string)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" + ");
+
+                Some(text(fl!("format_resolved", formats = formats)))
+            }
+            Some(Err(e)) => Some(text(e.clone()).style(text::danger)),
+            None => None,
+        };
+
+        column![
+            dir_row(vec![
+                text(format!("{}:", fl!("format_selector"))).into(),
+                text_input("bv*[height<=1080]+ba/b", &custom_format.selector)
+                    .on_input(Message::FormatSelectorInput)
+                    .into(),
+                text(format!("{}:", fl!("format_sort"))).into(),
+                text_input("vcodec:avc1,+size", &custom_format.sort)
+                    .on_input(Message::FormatSortInput)
+                    .into(),
+                button(text(fl!("test_format")))
+                    .on_press_maybe((!self.is_testing_format).then_some(Message::TestFormat))
+                    .into(),
+            ])
+            .spacing(SPACING)
+            .align_y(Alignment::Center),
+            custom_format
+                .validate()
+                .err()
+                .map(|e| text(e.to_string()).style(text::danger)),
+            result,
+        ]
+        .spacing(SPACING)
+    }
+
+    fn loudness_options(&self) -> Option<iced::widget::Column<'_, Message>> {
+        // the audio is only normalized when it's re-encoded
+        if !self.config.options.audio_format.reencodes() {
+            return None;
+        }
+
+        let loudness = &self.config.options.loudness;
+
+        let mut options = vec![
+            checkbox(loudness.normalize)
+                .label(fl!("normalize_loudness"))
+                .on_toggle(Message::ToggleNormalizeLoudness)
+                .into(),
+        ];
+
+        if loudness.normalize {
+            options.extend([
+                text(format!("{}:", fl!("loudness_target"))).into(),
+                text_input("-16", &self.loudness_target_input)
+                    .on_input(Message::LoudnessTargetInput)
+                    .width(Length::Fixed(80.))
+                    .into(),
+                text(format!("{}:", fl!("true_peak"))).into(),
+                text_input("-1.5", &self.true_peak_input)
+                    .on_input(Message::TruePeakInput)
+                    .width(Length::Fixed(80.))
+                    .into(),
+            ]);
+        }
+
+        Some(
+            column![
+                dir_row(options).spacing(SPACING).align_y(Alignment::Center),
+                self.loudness()
+                    .err()
+                    .map(|e| text(e.to_string()).style(text::danger)),
+            ]
+            .spacing(SPACING)
+            .padding(Padding::ZERO.horizontal(12).bottom(12)),
+        )
+    }
+
+    fn live_options(&self) -> iced::widget::Row<'_, Message> {
+        let live = &self.config.live;
+
+        dir_row(vec![
+            checkbox(live.live_from_start)
+                .label(fl!("live_from_start"))
+                .on_toggle(Message::ToggleLiveFromStart)
+                .into(),
+            text_input(
+                &fl!("wait_for_video"),
+                &live
+                    .wait_for_video
+                    .map(|v| v.to_string())
+                    .unwrap_or_default(),
+            )
+            .on_input(Message::LiveWaitForVideoInput)
+            .width(Length::Fixed(220.))
+            .into(),
+            text_input(
+                &fl!("max_recording_duration"),
+                &live.max_duration.map(|v| v.to_string()).unwrap_or_default(),
+            )
+            .on_input(Message::LiveMaxDurationInput)
+            .width(Length::Fixed(220.))
+            .into(),
+        ])
+        .spacing(SPACING)
+        .align_y(Alignment::Center)
+    }
+
+    fn update_netrc(&self) {
+        if let Err(e) = credentials::write_netrc(&self.config.credentials) {
+            tracing::error!("failed to write netrc file: {e}");
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        Subscription::batch([
+            iced::event::listen().map(Message::IcedEvent),
+            iced::time::every(Duration::from_secs(1)).map(|_| Message::CheckConfigFile),
+            iced::time::every(Duration::from_secs(15)).map(|_| Message::SchedulerTick),
+            if self.config.subscriptions.is_empty() {
+                Subscription::none()
+            } else {
+                iced::time::every(Duration::from_secs(60)).map(|_| Message::SyncSubscriptions)
+            },
+            if self.recording.is_some() {
+                iced::time::every(Duration::from_secs(1)).map(|_| Message::LiveTick)
+            } else {
+                Subscription::none()
+            },
+            if self.scheduled_jobs.is_empty() {
+                Subscription::none()
+            } else {
+                iced::time::every(Duration::from_secs(1)).map(|_| Message::ScheduledJobsTick)
+            },
+            if self.pending_retry.is_some() {
+                iced::time::every(Duration::from_secs(1)).map(|_| Message::RetryTick)
+            } else {
+                Subscription::none()
+            },
+        ])
+    }
+
+    pub fn end_download(&mut self, download_message: Option<Result<String, DownloadError>>) {
+        self.command.kill();
+        self.progress = None;
+        self.recording = None;
+        self.download_message = download_message;
+        if let Some(job) = self.current_job.take() {
+            self.log_download(&job);
+
+            // stopped downloads don't have a result
+            if let Some(result) = &self.download_message {
+                let notification = match result {
+                    Ok(message) if self.config.notifications.download_finished => Some(
+                        DownloadNotification::finished(message, &job, &self.finished_videos),
+                    ),
+                    Err(e) if self.config.notifications.download_failed => {
+                        Some(DownloadNotification::failed(&e.to_string(), &job))
+                    }
+                    _ => None,
+                };
+
+                if let Some(notification) = notification {
+                    notification.show(self.config.proxy(), self.sender.clone());
+                }
+
+                let error = result.as_ref().err().map(ToString::to_string);
+                self.record_history(job, error);
             }
-            None => {}
         }
+        self.start_next_job();
     }
 }
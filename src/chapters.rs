@@ -0,0 +1,158 @@
+//! Chapter embedding and splitting.
+//!
+//! yt-dlp doesn't number the files it splits, so the split files are tagged
+//! with their track number and chapter title after the download.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::probe::Chapter;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+fn chapter_template_default() -> String {
+    String::from("%(title)s/%(section_number)02d - %(section_title)s.%(ext)s")
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ChapterOptions {
+    pub embed: bool,
+    pub split: bool,
+    /// Output template of the split files, relative to the download folder
+    pub template: String,
+}
+
+impl Default for ChapterOptions {
+    fn default() -> Self {
+        Self {
+            embed: false,
+            split: false,
+            template: chapter_template_default(),
+        }
+    }
+}
+
+impl ChapterOptions {
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+
+        if self.embed {
+            options.push(String::from("--embed-chapters"));
+        }
+
+        if self.split {
+            let template = match self.template.trim() {
+                "" => chapter_template_default(),
+                template => template.to_string(),
+            };
+
+            options.extend([
+                String::from("--split-chapters"),
+                String::from("-o"),
+                format!("chapter:{template}"),
+                // the chapter titles are used to tag the split files
+                String::from("--print"),
+                String::from(r#"before_dl:__{"type": "chapters", "chapters": %(chapters)j}"#),
+            ]);
+        }
+
+        options
+    }
+}
+
+/// The split files of the video that's being downloaded
+#[derive(Debug, Default)]
+pub struct SplitChapters {
+    pub chapters: Vec<Chapter>,
+    /// Chapter number and path of each split file
+    pub tracks: Vec<(u32, PathBuf)>,
+}
+
+/// Parses `[SplitChapters] Chapter 001; Destination: <path>`
+pub fn parse_split_line(line: &str) -> Option<(u32, PathBuf)> {
+    let (number, destination) = line
+        .trim()
+        .strip_prefix("[SplitChapters] Chapter ")?
+        .split_once("; Destination: ")?;
+
+    Some((number.parse().ok()?, PathBuf::from(destination)))
+}
+
+/// Writes the track number and chapter title to every split file with ffmpeg
+pub fn tag_tracks(split: &SplitChapters) {
+    let total = split.tracks.len();
+
+    for (number, path) in &split.tracks {
+        let title = split
+            .chapters
+            .get((*number as usize).saturating_sub(1))
+            .and_then(|chapter| chapter.title.as_deref());
+
+        match tag_track(path, *number, total, title) {
+            Ok(()) => tracing::info!("tagged track {number}: {}", path.display()),
+            Err(e) => tracing::error!("failed to tag track {}: {e}", path.display()),
+        }
+    }
+}
+
+fn tag_track(path: &Path, number: u32, total: usize, title: Option<&str>) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no file name"))?;
+    let tagged = path.with_file_name(format!(".tagging-{}", file_name.to_string_lossy()));
+
+    let mut command = std::process::Command::new("ffmpeg");
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    command
+        .args(["-y", "-v", "error", "-i"])
+        .arg(path)
+        .args(["-map", "0", "-c", "copy", "-metadata"])
+        .arg(format!("track={number}/{total}"));
+
+    if let Some(title) = title {
+        command.arg("-metadata").arg(format!("title={title}"));
+    }
+
+    let output = command.arg(&tagged).stdin(Stdio::null()).output()?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&tagged);
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    std::fs::rename(tagged, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_split_line() {
+        assert_eq!(
+            parse_split_line(
+                "[SplitChapters] Chapter 003; Destination: /music/Album/03 - Song.mp3\n"
+            ),
+            Some((3, PathBuf::from("/music/Album/03 - Song.mp3")))
+        );
+        assert_eq!(
+            parse_split_line("[SplitChapters] Splitting video by chapters; 12 chapters found"),
+            None
+        );
+    }
+}
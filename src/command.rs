@@ -13,6 +13,9 @@ use crate::error::DownloadError;
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+#[cfg(unix)]
+const SIGINT: i32 = 2;
+
 #[derive(Default)]
 pub struct Command {
     pub shared_child: Option<Arc<SharedChild>>,
@@ -55,9 +58,43 @@ impl Command {
         }
     }
 
+    /// Stops yt-dlp like Ctrl+C does, so it can finish writing the file
+    /// before exiting, returns `false` when that's not supported
+    pub fn interrupt(&self) -> bool {
+        let Some(child) = &self.shared_child else {
+            return false;
+        };
+
+        #[cfg(unix)]
+        {
+            use shared_child::unix::SharedChildExt;
+
+            match child.send_signal(SIGINT) {
+                Ok(()) => {
+                    tracing::debug!("interrupted child process");
+                    true
+                }
+                Err(e) => {
+                    tracing::error!("failed to interrupt child process {e}");
+                    false
+                }
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = child;
+            false
+        }
+    }
+
+    pub fn id(&self) -> Option<u32> {
+        self.shared_child.as_ref().map(|child| child.id())
+    }
+
     pub fn start(
         &mut self,
-        mut args: Vec<&str>,
+        mut args: Vec<String>,
         bin_path: Option<PathBuf>,
         sender: UnboundedSender<crate::Message>,
         videos_num: usize,
@@ -80,6 +117,8 @@ impl Command {
             "--print",
             r#"playlist:__{"type": "end_of_playlist"}"#,
             "--print",
+            r#"after_move:__{"type": "downloaded", "resolution": %(resolution)j, "filepath": %(filepath)j, "title": %(title)j, "url": %(webpage_url)j, "thumbnail": %(thumbnail)j}"#,
+            "--print",
             r#"after_video:__{"type": "end_of_video"}"#,
         ];
 
@@ -103,14 +142,14 @@ impl Command {
         let progess_template = [
             "--progress-template",
             template,
-            // "--progress-template",
-            // r#"postprocess:__{"type": "post_processing", "status": "%(progress.status)s"}"#,
+            "--progress-template",
+            r#"postprocess:__{"type": "post_processing", "status": "%(progress.status)s", "postprocessor": "%(progress.postprocessor)s"}"#,
         ];
 
-        args.extend_from_slice(&print);
-        args.extend_from_slice(&progess_template);
+        args.extend(print.map(String::from));
+        args.extend(progess_template.map(String::from));
 
-        args.push("--no-quiet");
+        args.push(String::from("--no-quiet"));
 
         let Ok(shared_child) = SharedChild::spawn(
             command
@@ -129,7 +168,7 @@ impl Command {
             return Some(Err(DownloadError::Other));
         };
 
-        if let Some(stderr) = child.take_stderr() {
+        let stderr_thread = child.take_stderr().map(|stderr| {
             let sender = sender.clone();
             std::thread::spawn(move || {
                 let reader = BufReader::new(stderr);
@@ -138,8 +177,8 @@ impl Command {
                         .unbounded_send(crate::Message::ProgressEvent(format!("stderr:{line}")))
                         .unwrap_or_else(|e| tracing::error!("failed to send stderr: {e}"));
                 }
-            });
-        }
+            })
+        });
 
         if let Some(stdout) = child.take_stdout() {
             std::thread::spawn(move || {
@@ -162,6 +201,17 @@ impl Command {
 
                     buffer.clear();
                 }
+
+                // the errors should be handled before the exit
+                if let Some(stderr_thread) = stderr_thread {
+                    let _ = stderr_thread.join();
+                }
+
+                let success = child.wait().is_ok_and(|status| status.success());
+
+                sender
+                    .unbounded_send(crate::Message::DownloadProcessExited(child.id(), success))
+                    .unwrap_or_else(|e| tracing::error!("failed to send process exit: {e}"));
             });
         }
 
@@ -0,0 +1,383 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fl;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Cookie names that are only set after logging in to a site
+const LOGIN_COOKIES: [&str; 8] = [
+    // YouTube/Google
+    "LOGIN_INFO",
+    "SID",
+    "__Secure-3PSID",
+    "SAPISID",
+    // common names used by other sites
+    "sessionid",
+    "session",
+    "auth_token",
+    "access_token",
+];
+
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CookiesSource {
+    #[default]
+    File,
+    Browser,
+}
+
+impl core::fmt::Display for CookiesSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CookiesSource::File => f.write_str(&fl!("cookies_file")),
+            CookiesSource::Browser => f.write_str(&fl!("cookies_browser")),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Browser {
+    Brave,
+    Chrome,
+    Chromium,
+    Edge,
+    #[default]
+    Firefox,
+    Opera,
+    Safari,
+    Vivaldi,
+    Whale,
+}
+
+impl Browser {
+    pub const ALL: [Browser; 9] = [
+        Browser::Brave,
+        Browser::Chrome,
+        Browser::Chromium,
+        Browser::Edge,
+        Browser::Firefox,
+        Browser::Opera,
+        Browser::Safari,
+        Browser::Vivaldi,
+        Browser::Whale,
+    ];
+
+    pub fn options(&self) -> &str {
+        match self {
+            Browser::Brave => "brave",
+            Browser::Chrome => "chrome",
+            Browser::Chromium => "chromium",
+            Browser::Edge => "edge",
+            Browser::Firefox => "firefox",
+            Browser::Opera => "opera",
+            Browser::Safari => "safari",
+            Browser::Vivaldi => "vivaldi",
+            Browser::Whale => "whale",
+        }
+    }
+}
+
+impl core::fmt::Display for Browser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Browser::Brave => write!(f, "Brave"),
+            Browser::Chrome => write!(f, "Chrome"),
+            Browser::Chromium => write!(f, "Chromium"),
+            Browser::Edge => write!(f, "Edge"),
+            Browser::Firefox => write!(f, "Firefox"),
+            Browser::Opera => write!(f, "Opera"),
+            Browser::Safari => write!(f, "Safari"),
+            Browser::Vivaldi => write!(f, "Vivaldi"),
+            Browser::Whale => write!(f, "Whale"),
+        }
+    }
+}
+
+/// The keyring used to decrypt Chromium cookies on Linux
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Keyring {
+    #[default]
+    Automatic,
+    BasicText,
+    GnomeKeyring,
+    KWallet,
+    KWallet5,
+    KWallet6,
+}
+
+impl Keyring {
+    pub const ALL: [Keyring; 6] = [
+        Keyring::Automatic,
+        Keyring::BasicText,
+        Keyring::GnomeKeyring,
+        Keyring::KWallet,
+        Keyring::KWallet5,
+        Keyring::KWallet6,
+    ];
+
+    pub fn options(&self) -> Option<&str> {
+        match self {
+            Keyring::Automatic => None,
+            Keyring::BasicText => Some("basictext"),
+            Keyring::GnomeKeyring => Some("gnomekeyring"),
+            Keyring::KWallet => Some("kwallet"),
+            Keyring::KWallet5 => Some("kwallet5"),
+            Keyring::KWallet6 => Some("kwallet6"),
+        }
+    }
+}
+
+impl core::fmt::Display for Keyring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Keyring::Automatic => f.write_str(&fl!("keyring_automatic")),
+            Keyring::BasicText => write!(f, "basictext"),
+            Keyring::GnomeKeyring => write!(f, "gnomekeyring"),
+            Keyring::KWallet => write!(f, "kwallet"),
+            Keyring::KWallet5 => write!(f, "kwallet5"),
+            Keyring::KWallet6 => write!(f, "kwallet6"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct BrowserCookies {
+    pub browser: Browser,
+    pub keyring: Keyring,
+    pub profile: String,
+    /// Firefox container name
+    pub container: String,
+}
+
+impl BrowserCookies {
+    /// Formats the value of `--cookies-from-browser`:
+    /// `BROWSER[+KEYRING][:PROFILE][::CONTAINER]`
+    pub fn options(&self) -> String {
+        let mut spec = self.browser.options().to_string();
+
+        if let Some(keyring) = self.keyring.options() {
+            spec.push('+');
+            spec.push_str(keyring);
+        }
+
+        let profile = self.profile.trim();
+        let container = self.container.trim();
+
+        if !profile.is_empty() {
+            spec.push(':');
+            spec.push_str(profile);
+        }
+
+        if !container.is_empty() {
+            if profile.is_empty() {
+                spec.push(':');
+            }
+            spec.push_str("::");
+            spec.push_str(container);
+        }
+
+        spec
+    }
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("Enter a download link to check the cookies against")]
+    NoUrl,
+    #[error("yt-dlp binary is missing")]
+    YtDlpMissing,
+    #[error("Failed to extract cookies: {0}")]
+    Extraction(String),
+    #[error("{0}")]
+    Io(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct CookiesCheck {
+    pub domain: String,
+    /// Number of cookies that were extracted from the browser
+    pub total: usize,
+    /// Number of cookies that belong to the checked domain
+    pub domain_cookies: usize,
+    pub has_login_cookies: bool,
+    /// yt-dlp can extract the cookies then fail extracting the video itself
+    pub extraction_error: Option<String>,
+}
+
+impl core::fmt::Display for CookiesCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let summary = fl!(
+            "cookies_check_summary",
+            total = self.total,
+            domain_cookies = self.domain_cookies,
+            domain = self.domain.as_str()
+        );
+        let login = if self.has_login_cookies {
+            fl!("cookies_check_logged_in")
+        } else {
+            fl!("cookies_check_not_logged_in")
+        };
+        write!(f, "{summary}, {login}")?;
+
+        if let Some(e) = &self.extraction_error {
+            write!(f, " ({e})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Extracts the browser cookies by running yt-dlp on `url` without downloading anything,
+/// the cookies are saved to a temporary file to check whether login cookies were found
+pub async fn check_browser_cookies(
+    bin_path: Option<PathBuf>,
+    cookies: BrowserCookies,
+    url: String,
+) -> Result<CookiesCheck, Error> {
+    let (sender, receiver) = iced::futures::channel::oneshot::channel();
+
+    std::thread::spawn(move || {
+        let _ = sender.send(run_cookies_check(bin_path, &cookies, &url));
+    });
+
+    receiver
+        .await
+        .map_err(|e| Error::Io(e.to_string()))
+        .and_then(|res| res)
+}
+
+fn run_cookies_check(
+    bin_path: Option<PathBuf>,
+    cookies: &BrowserCookies,
+    url: &str,
+) -> Result<CookiesCheck, Error> {
+    let url = url.trim().split(' ').next().unwrap_or_default();
+
+    let Some(domain) = url::Url::parse(url).ok().and_then(|url| {
+        url.host_str()
+            .map(|host| host.trim_start_matches("www.").to_string())
+    }) else {
+        return Err(Error::NoUrl);
+    };
+
+    // the file contains every cookie from the browser, so it's written in a private
+    // directory (0700 with a random name) that is removed when it's dropped
+    let cookies_dir = tempfile::Builder::new()
+        .prefix("ytdlp-gui-cookies-check-")
+        .tempdir()
+        .map_err(|e| Error::Io(e.to_string()))?;
+    let cookies_file = cookies_dir.path().join("cookies.txt");
+
+    let mut command = std::process::Command::new(bin_path.unwrap_or("yt-dlp".into()));
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = command
+        .args([
+            "--cookies-from-browser",
+            &cookies.options(),
+            "--cookies",
+            &cookies_file.to_string_lossy(),
+            "--skip-download",
+            "--no-playlist",
+            "--no-warnings",
+            url,
+        ])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|_| Error::YtDlpMissing)?;
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let error = stderr
+        .lines()
+        .rev()
+        .find_map(|line| line.strip_prefix("ERROR: "))
+        .map(ToString::to_string);
+
+    let parsed = parse_cookies_file(&cookies_file, &domain);
+
+    if let Err(e) = cookies_dir.close() {
+        tracing::error!("failed to remove the cookies check directory: {e}");
+    }
+
+    match parsed {
+        Ok((total, domain_cookies, has_login_cookies)) if total > 0 => Ok(CookiesCheck {
+            domain,
+            total,
+            domain_cookies,
+            has_login_cookies,
+            extraction_error: error,
+        }),
+        _ => Err(Error::Extraction(error.unwrap_or_else(|| {
+            format!("no cookies found in {}", cookies.browser)
+        }))),
+    }
+}
+
+/// Reads a Netscape formatted cookies file, returns the total number of cookies,
+/// the number of cookies for `domain`, and whether any of them is a login cookie
+fn parse_cookies_file(path: &Path, domain: &str) -> std::io::Result<(usize, usize, bool)> {
+    let content = std::fs::read_to_string(path)?;
+
+    let mut total = 0;
+    let mut domain_cookies = 0;
+    let mut has_login_cookies = false;
+
+    for line in content.lines() {
+        // http only cookies are prefixed with `#HttpOnly_`, the rest of `#` lines are comments
+        let line = line.strip_prefix("#HttpOnly_").unwrap_or(line);
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [cookie_domain, _, _, _, _, name, ..] = fields[..] else {
+            continue;
+        };
+
+        total += 1;
+
+        let cookie_domain = cookie_domain.trim_start_matches('.');
+        if domain == cookie_domain || domain.ends_with(&format!(".{cookie_domain}")) {
+            domain_cookies += 1;
+            has_login_cookies |= LOGIN_COOKIES.contains(&name);
+        }
+    }
+
+    Ok((total, domain_cookies, has_login_cookies))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browser_cookies_options() {
+        let mut cookies = BrowserCookies::default();
+        assert_eq!(cookies.options(), "firefox");
+
+        cookies.container = String::from("Personal");
+        assert_eq!(cookies.options(), "firefox:::Personal");
+
+        cookies.profile = String::from("default-release");
+        assert_eq!(cookies.options(), "firefox:default-release::Personal");
+
+        let cookies = BrowserCookies {
+            browser: Browser::Chromium,
+            keyring: Keyring::GnomeKeyring,
+            profile: String::from("Profile 1"),
+            container: String::new(),
+        };
+        assert_eq!(cookies.options(), "chromium+gnomekeyring:Profile 1");
+    }
+}
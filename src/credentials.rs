@@ -0,0 +1,374 @@
+//! Site login credentials.
+//!
+//! Only the domain, machine name and username are stored in `config.toml`, the passwords
+//! are kept in a netrc file managed by the app which is only readable by the user.
+//!
+//! The logins of a download are passed to yt-dlp in a temporary netrc file that only has
+//! the logins matching the download's links, so the passwords never show up in the arguments.
+
+use std::{fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::redact::{Secret, register_secret};
+
+#[derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Credential {
+    /// The site's domain e.g. `vimeo.com`, its subdomains match as well.
+    /// It's also the machine name in the managed netrc file
+    #[serde(alias = "extractor")]
+    pub domain: String,
+    /// The netrc machine name of the site's yt-dlp extractor e.g. `vimeo`,
+    /// or `watchnebula` for `nebula.tv`
+    pub machine: String,
+    pub username: String,
+    #[serde(skip)]
+    pub password: Secret,
+}
+
+impl Credential {
+    fn domain(&self) -> String {
+        self.domain
+            .trim()
+            .trim_start_matches("www.")
+            .trim_matches('.')
+            .to_lowercase()
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        let domain = self.domain();
+
+        !domain.is_empty()
+            && url::Url::parse(url)
+                .ok()
+                .and_then(|url| url.host_str().map(|host| host.to_lowercase()))
+                .is_some_and(|host| host == domain || host.ends_with(&format!(".{domain}")))
+    }
+}
+
+/// Finds the login for the site of `url`
+pub fn credential_for<'a>(credentials: &'a [Credential], url: &str) -> Option<&'a Credential> {
+    credentials
+        .iter()
+        .find(|credential| credential.matches(url))
+}
+
+pub fn netrc_path() -> PathBuf {
+    dirs::config_dir()
+        .expect("config directory")
+        .join("ytdlp-gui/netrc")
+}
+
+/// Fills in the passwords of `credentials` from the managed netrc file
+pub fn load_passwords(credentials: &mut [Credential]) {
+    let content = match fs::read_to_string(netrc_path()) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+        Err(e) => {
+            tracing::error!("failed to read netrc file: {e}");
+            return;
+        }
+    };
+
+    let entries = parse_netrc(&content);
+
+    for credential in credentials {
+        if let Some((_, _, password)) = entries.iter().find(|(machine, login, _)| {
+            machine.trim() == credential.domain.trim() && *login == credential.username
+        }) {
+            register_secret(password);
+            credential.password = Secret::new(password.clone());
+        }
+    }
+}
+
+/// Saves the passwords of `credentials` in the managed netrc file
+pub fn write_netrc(credentials: &[Credential]) -> io::Result<()> {
+    write_private(&netrc_path(), &format_netrc(credentials))
+}
+
+/// Writes a temporary netrc file with the logins of the sites of `links`, `None` when there's no login for them.
+///
+/// The file is only readable by the user, and removed when it's dropped
+pub fn download_netrc(
+    credentials: &[Credential],
+    links: &[String],
+) -> io::Result<Option<tempfile::NamedTempFile>> {
+    let content = format_download_netrc(credentials, links);
+
+    if content.is_empty() {
+        return Ok(None);
+    }
+
+    let mut file = tempfile::Builder::new()
+        .prefix("ytdlp-gui-netrc-")
+        .tempfile()?;
+    io::Write::write_all(&mut file, content.as_bytes())?;
+
+    Ok(Some(file))
+}
+
+/// The entries of the logins matching `links`, every link only gets the login of its own site
+fn format_download_netrc(credentials: &[Credential], links: &[String]) -> String {
+    let mut machines: Vec<String> = Vec::new();
+    let mut content = String::new();
+
+    for credential in links
+        .iter()
+        .filter_map(|link| credential_for(credentials, link))
+    {
+        let machine = credential.machine.trim().to_string();
+
+        if machine.is_empty() {
+            tracing::warn!(
+                "the login for {} has no netrc machine name, it's not used",
+                credential.domain.trim()
+            );
+            continue;
+        }
+
+        if machines.contains(&machine) {
+            continue;
+        }
+
+        register_secret(credential.password.expose());
+
+        content.push_str(&format!(
+            "machine {} login {} password {}\n",
+            quote(&machine),
+            quote(&credential.username),
+            quote(credential.password.expose())
+        ));
+        machines.push(machine);
+    }
+
+    content
+}
+
+/// Writes a file that's only readable by the user
+fn write_private(path: &std::path::Path, content: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)?;
+
+    #[cfg(unix)]
+    {
+        // the mode only applies when creating the file
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+
+    io::Write::write_all(&mut file, content.as_bytes())
+}
+
+fn format_netrc(credentials: &[Credential]) -> String {
+    credentials
+        .iter()
+        .filter(|credential| !credential.domain.trim().is_empty())
+        .map(|credential| {
+            format!(
+                "machine {} login {} password {}\n",
+                quote(credential.domain.trim()),
+                quote(&credential.username),
+                quote(credential.password.expose())
+            )
+        })
+        .collect()
+}
+
+fn quote(token: &str) -> String {
+    if !token.is_empty() && !token.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+        return token.to_string();
+    }
+
+    format!(r#""{}""#, token.replace('\\', r"\\").replace('"', r#"\""#))
+}
+
+fn tokenize(content: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+
+        let mut token = String::new();
+
+        if c == '"' {
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => token.extend(chars.next()),
+                    c => token.push(c),
+                }
+            }
+        } else {
+            token.push(c);
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                token.push(c);
+            }
+        }
+
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+/// Returns `(machine, login, password)` entries
+fn parse_netrc(content: &str) -> Vec<(String, String, String)> {
+    let mut entries = Vec::new();
+    let mut tokens = tokenize(content).into_iter();
+
+    let mut entry: Option<(String, String, String)> = None;
+
+    while let Some(token) = tokens.next() {
+        match token.as_str() {
+            "machine" => {
+                entries.extend(entry.take());
+                entry = Some((
+                    tokens.next().unwrap_or_default(),
+                    String::new(),
+                    String::new(),
+                ));
+            }
+            "login" => {
+                if let Some(entry) = &mut entry {
+                    entry.1 = tokens.next().unwrap_or_default();
+                }
+            }
+            "password" => {
+                if let Some(entry) = &mut entry {
+                    entry.2 = tokens.next().unwrap_or_default();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    entries.extend(entry);
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_netrc_roundtrip() {
+        let credentials = vec![
+            Credential {
+                domain: String::from("vimeo.com"),
+                username: String::from("user@example.com"),
+                password: Secret::new(String::from("correct horse \"battery\"")),
+                ..Default::default()
+            },
+            Credential {
+                domain: String::from("nebula.tv"),
+                username: String::from("user"),
+                password: Secret::new(String::from(r"back\slash")),
+                ..Default::default()
+            },
+        ];
+
+        let netrc = format_netrc(&credentials);
+
+        assert_eq!(
+            parse_netrc(&netrc),
+            vec![
+                (
+                    String::from("vimeo.com"),
+                    String::from("user@example.com"),
+                    String::from("correct horse \"battery\"")
+                ),
+                (
+                    String::from("nebula.tv"),
+                    String::from("user"),
+                    String::from(r"back\slash")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_credential_for() {
+        let credentials = vec![Credential {
+            domain: String::from("vimeo.com"),
+            ..Default::default()
+        }];
+
+        assert!(credential_for(&credentials, "https://vimeo.com/123").is_some());
+        assert!(credential_for(&credentials, "https://player.vimeo.com/video/123").is_some());
+        assert!(credential_for(&credentials, "https://VIMEO.com/123").is_some());
+        assert!(credential_for(&credentials, "https://notvimeo.com/123").is_none());
+        assert!(credential_for(&credentials, "https://vimeo.com.attacker.com/123").is_none());
+        assert!(credential_for(&credentials, "https://notvimeo.attacker.com/123").is_none());
+        assert!(credential_for(&credentials, "https://youtube.com/watch?v=123").is_none());
+    }
+
+    #[test]
+    fn test_download_netrc() {
+        let credentials = vec![
+            Credential {
+                domain: String::from(" vimeo.com "),
+                machine: String::from("vimeo"),
+                username: String::from("user"),
+                password: Secret::new(String::from("vimeo password")),
+            },
+            Credential {
+                domain: String::from("nebula.tv"),
+                machine: String::from(" watchnebula "),
+                username: String::from("user"),
+                password: Secret::new(String::from("nebula")),
+            },
+            Credential {
+                domain: String::from("music.abc.com"),
+                username: String::from("user"),
+                password: Secret::new(String::from("abc")),
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            format_download_netrc(
+                &credentials,
+                &[
+                    String::from("https://vimeo.com/1"),
+                    String::from("https://player.vimeo.com/video/2"),
+                    String::from("https://youtube.com/watch?v=3"),
+                ]
+            ),
+            "machine vimeo login user password \"vimeo password\"\n"
+        );
+        assert_eq!(
+            format_download_netrc(&credentials, &[String::from("https://nebula.tv/videos/1")]),
+            "machine watchnebula login user password nebula\n"
+        );
+        // the machine name can't be guessed from the site
+        assert_eq!(
+            format_download_netrc(&credentials, &[String::from("https://music.abc.com/1")]),
+            ""
+        );
+        assert_eq!(
+            format_download_netrc(
+                &credentials,
+                &[String::from("https://youtube.com/watch?v=3")]
+            ),
+            ""
+        );
+    }
+}
@@ -0,0 +1,506 @@
+use std::{path::PathBuf, sync::Arc};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    YtGUI,
+    app::DownloadType,
+    chapters::{ChapterOptions, SplitChapters},
+    cookies::CookiesSource,
+    credentials,
+    error::DownloadError,
+    fl,
+    format_selector::CustomFormat,
+    livestream::{LiveOptions, Recording},
+    media_options::{Loudness, LoudnessError, Options, playlist_options},
+    metadata::Tags,
+    playlist_report::PlaylistReport,
+    scheduled::{self, ScheduledJob},
+    scheduler::{self, RuleAction},
+    sections::{DownloadSections, SectionError, parse_ranges},
+    sponsorblock::SponsorBlockOption,
+    thumbnail::ThumbnailOptions,
+};
+
+/// A snapshot of the options a download was started with,
+/// so it can wait in the queue while the options are changed for the next one
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DownloadJob {
+    pub links: Vec<String>,
+    pub download_type: DownloadType,
+    pub options: Options,
+    pub is_playlist: bool,
+    /// Set when recording a livestream
+    #[serde(default)]
+    pub live: Option<LiveOptions>,
+    #[serde(default)]
+    pub sections: DownloadSections,
+    #[serde(default)]
+    pub chapters: ChapterOptions,
+    /// Set when the metadata is embedded
+    #[serde(default)]
+    pub metadata: Option<Tags>,
+    pub get_thumbnail: bool,
+    #[serde(default)]
+    pub thumbnails: ThumbnailOptions,
+    /// Only used for video downloads
+    #[serde(default)]
+    pub custom_format: CustomFormat,
+    pub sponsorblock: SponsorBlockOption,
+    pub download_folder: PathBuf,
+    pub preset: Option<String>,
+    /// Passed to `--download-archive` so downloaded entries are recorded
+    #[serde(default)]
+    pub download_archive: Option<PathBuf>,
+    /// The files yt-dlp wrote, filled while downloading
+    #[serde(default)]
+    pub files: Vec<PathBuf>,
+    /// Set when retrying the failed items of a playlist, only these entries are downloaded
+    #[serde(default)]
+    pub only_ids: Vec<String>,
+    /// The logins of the links, written when the job starts and removed when it's dropped
+    #[serde(skip)]
+    pub netrc: Option<Arc<tempfile::NamedTempFile>>,
+}
+
+impl DownloadJob {
+    pub fn label(&self) -> String {
+        format!("[{}] {}", self.download_type, self.links.join(" "))
+    }
+}
+
+impl YtGUI {
+    /// Checks the download folder and the space separated links,
+    /// the error is shown in the download message when they're invalid
+    pub fn validate_links(&mut self, link: &str) -> Option<Vec<String>> {
+        self.config.download_folder = PathBuf::from(
+            shellexpand::tilde(&self.config.download_folder.display().to_string()).to_string(),
+        );
+
+        if !self.config.download_folder.exists() {
+            self.progress = None;
+            self.download_message = Some(Err(DownloadError::DownloadDir(
+                self.config.download_folder.clone(),
+            )));
+            return None;
+        }
+
+        let mut links = Vec::new();
+
+        for (i, link) in link.trim().split(' ').enumerate() {
+            if Url::parse(link).is_err() {
+                self.progress = None;
+                self.download_message = Some(Err(DownloadError::InvalidURL(i + 1)));
+                return None;
+            }
+
+            self.save_config().expect("update config file");
+
+            if link.is_empty() {
+                self.progress = None;
+                self.download_message = Some(Err(DownloadError::NoDownloadURL));
+                return None;
+            }
+
+            links.push(link.to_string());
+        }
+
+        if self.embeds_metadata()
+            && let Err(e) = self.tags.validate()
+        {
+            self.progress = None;
+            self.download_message = Some(Err(DownloadError::Tags(e)));
+            return None;
+        }
+
+        if self.download_type == DownloadType::Video
+            && let Err(e) = self.config.custom_format.validate()
+        {
+            self.progress = None;
+            self.download_message = Some(Err(DownloadError::Format(e)));
+            return None;
+        }
+
+        if let Err(e) = self.loudness() {
+            self.progress = None;
+            self.download_message = Some(Err(DownloadError::Loudness(e)));
+            return None;
+        }
+
+        if let Err(e) = self.download_sections(&links) {
+            self.progress = None;
+            self.download_message = Some(Err(DownloadError::Section(e)));
+            return None;
+        }
+
+        Some(links)
+    }
+
+    /// Checks the loudness inputs when normalizing audio downloads
+    pub fn loudness(&self) -> Result<(), LoudnessError> {
+        if self.download_type == DownloadType::Audio && self.config.options.loudness.normalize {
+            Loudness::parse_target(&self.loudness_target_input)?;
+            Loudness::parse_true_peak(&self.true_peak_input)?;
+        }
+
+        Ok(())
+    }
+
+    /// The tag form is only used for audio downloads
+    pub fn embeds_metadata(&self) -> bool {
+        self.download_type == DownloadType::Audio && self.config.embed_metadata
+    }
+
+    /// The sections to download, the ranges are checked against the video's duration
+    /// when its info was fetched
+    pub fn download_sections(&self, links: &[String]) -> Result<DownloadSections, SectionError> {
+        let sections = DownloadSections {
+            ranges: parse_ranges(&self.sections_input)?,
+            chapters: self.chapters_input.trim().to_string(),
+            force_keyframes_at_cuts: self.force_keyframes,
+        };
+
+        if let Some(Ok(info)) = &self.media_info
+            && let [link] = links
+            && *link == self.media_info_url
+            && let Some(duration) = info.duration
+        {
+            for range in &sections.ranges {
+                range.check_duration(duration)?;
+            }
+        }
+
+        Ok(sections)
+    }
+
+    /// The tags of the form are for the video their info was fetched from,
+    /// other downloads keep the ones from the site
+    fn job_tags(&self, links: &[String]) -> Tags {
+        match links {
+            [link] if !self.is_playlist && *link == self.media_info_url => self.tags.clone(),
+            _ => Tags::default(),
+        }
+    }
+
+    pub fn new_job(&self, links: Vec<String>) -> DownloadJob {
+        DownloadJob {
+            download_type: self.download_type,
+            options: self.config.options,
+            is_playlist: self.is_playlist,
+            live: self.is_live.then_some(self.config.live),
+            chapters: self.config.chapters.clone(),
+            metadata: self.embeds_metadata().then(|| self.job_tags(&links)),
+            sections: self.download_sections(&links).unwrap_or_default(),
+            get_thumbnail: self.get_thumbnail,
+            thumbnails: self.config.thumbnails,
+            custom_format: match self.download_type {
+                DownloadType::Video => self.config.custom_format.clone(),
+                DownloadType::Audio => CustomFormat::default(),
+            },
+            sponsorblock: self.sponsorblock,
+            download_folder: self.config.download_folder.clone(),
+            preset: self.config.active_preset.clone(),
+            download_archive: None,
+            files: Vec::new(),
+            only_ids: Vec::new(),
+            netrc: None,
+            links,
+        }
+    }
+
+    /// Starts `job` right away, or adds it to the queue if another download is running
+    /// or the queue is paused by the bandwidth schedule
+    pub fn enqueue_job(&mut self, job: DownloadJob) {
+        self.update_bandwidth_schedule();
+
+        if self.is_downloading() || self.queue_paused {
+            self.queue.push_back(job);
+
+            if !self.is_downloading() {
+                self.download_message = Some(Ok(fl!("queue_paused")));
+            }
+
+            return;
+        }
+
+        self.start_job(job);
+    }
+
+    pub fn schedule_job(&mut self, job: DownloadJob, start_time: DateTime<Local>) {
+        tracing::info!("scheduled download at {start_time}");
+
+        let index = self
+            .scheduled_jobs
+            .partition_point(|scheduled| scheduled.start_at <= start_time.timestamp());
+
+        self.scheduled_jobs.insert(
+            index,
+            ScheduledJob {
+                job,
+                start_at: start_time.timestamp(),
+            },
+        );
+        self.save_scheduled_jobs();
+    }
+
+    /// Moves the scheduled jobs whose time has come to the queue,
+    /// jobs that were due while the app was closed start right away
+    pub fn start_due_jobs(&mut self) {
+        let now = Local::now();
+
+        let due = self
+            .scheduled_jobs
+            .partition_point(|scheduled| scheduled.is_due(now));
+
+        if due == 0 {
+            return;
+        }
+
+        let jobs: Vec<ScheduledJob> = self.scheduled_jobs.drain(..due).collect();
+        self.save_scheduled_jobs();
+
+        for scheduled in jobs {
+            tracing::info!("starting scheduled download: {}", scheduled.job.label());
+            self.enqueue_job(scheduled.job);
+        }
+    }
+
+    pub fn save_scheduled_jobs(&self) {
+        if let Err(e) = scheduled::save(&self.scheduled_jobs) {
+            tracing::error!("failed to save scheduled downloads: {e}");
+        }
+    }
+
+    /// Whether yt-dlp is running or a failed download is waiting to be retried
+    pub fn is_downloading(&self) -> bool {
+        self.command.is_running() || self.pending_retry.is_some()
+    }
+
+    pub fn start_next_job(&mut self) {
+        if self.is_downloading() || self.queue_paused {
+            return;
+        }
+
+        if let Some(job) = self.queue.pop_front() {
+            self.start_job(job);
+        }
+    }
+
+    pub fn start_job(&mut self, mut job: DownloadJob) {
+        self.progress = None;
+        self.playlist_progress = None;
+        self.download_warning = None;
+        self.downloaded_resolution = None;
+        self.last_downloaded = None;
+        self.hook_errors.clear();
+        self.finished_videos.clear();
+        self.retry_attempt = 0;
+        self.playlist_report = job.is_playlist.then(|| PlaylistReport::new(job.clone()));
+        self.postprocessing_steps.clear();
+        self.recording = job.live.map(|_| Recording::default());
+        // split audio files are tagged as the tracks of an album
+        self.split_chapters = (job.chapters.split && job.download_type == DownloadType::Audio)
+            .then(SplitChapters::default);
+
+        // after the report's copy of the job so the logins aren't kept around with it
+        job.netrc = match credentials::download_netrc(&self.config.credentials, &job.links) {
+            Ok(netrc) => netrc.map(Arc::new),
+            Err(e) => {
+                tracing::error!("failed to write the netrc file of the download: {e}");
+                None
+            }
+        };
+
+        let args = self.download_args(&job);
+
+        self.download_message = self.command.start(
+            args,
+            self.config.bin_path.clone(),
+            self.sender.clone(),
+            job.links.len(),
+        );
+        self.current_job = Some(job);
+    }
+
+    /// Applies the bandwidth rule for the current time, no new downloads are started
+    /// while a `pause` rule is active, and the queue is resumed when leaving it
+    pub fn update_bandwidth_schedule(&mut self) {
+        let rule =
+            scheduler::active_rule(&self.config.bandwidth_schedule, Local::now().naive_local());
+
+        let paused = rule
+            .as_ref()
+            .is_some_and(|rule| rule.action == RuleAction::Pause);
+
+        self.active_rule = rule;
+
+        if paused == self.queue_paused {
+            return;
+        }
+
+        self.queue_paused = paused;
+
+        if paused {
+            // the running download finishes, only the next ones wait
+            tracing::info!("pausing the download queue because of the bandwidth schedule");
+        } else {
+            tracing::info!("resuming the download queue");
+            self.start_next_job();
+        }
+    }
+
+    /// Builds the yt-dlp arguments for downloading `job`
+    pub fn download_args(&self, job: &DownloadJob) -> Vec<String> {
+        let mut args = job.links.clone();
+
+        match job.download_type {
+            DownloadType::Video => {
+                args.extend(job.custom_format.options(&job.options));
+
+                // after downloading a video with a specific format
+                // yt-dlp sometimes downloads the audio and video seprately
+                // then merge them in a different format
+                // this enforces the chosen format by the user
+                // MOV, AVI and FLV are re-encoded since the streams don't fit in them
+                args.extend(job.options.video_format.conversion_options());
+            }
+            DownloadType::Audio => {
+                args.extend(job.options.audio_options());
+            }
+        }
+
+        args.extend(job.thumbnails.options(job.get_thumbnail, job.download_type));
+
+        tracing::debug!("{args:#?}");
+
+        args.extend(self.cookies_args());
+
+        if let Some(netrc) = &job.netrc {
+            args.push(String::from("--netrc"));
+            args.push(String::from("--netrc-location"));
+            args.push(netrc.path().to_string_lossy().to_string());
+        }
+
+        let mut network = self.config.network_options(job.preset.as_deref()).clone();
+
+        match self.active_rule.as_ref().map(|rule| &rule.action) {
+            Some(RuleAction::Unlimited) => network.limit_rate.clear(),
+            Some(RuleAction::Limit(rate)) => network.limit_rate = rate.clone(),
+            Some(RuleAction::Pause) | None => {}
+        }
+
+        args.extend(network.options());
+
+        if let Some(download_archive) = &job.download_archive {
+            args.push(String::from("--download-archive"));
+            args.push(download_archive.to_string_lossy().to_string());
+        }
+
+        if let Some(live) = &job.live {
+            args.extend(live.options());
+        }
+
+        args.extend(job.sections.options());
+
+        args.extend(job.chapters.options());
+
+        if let Some(tags) = &job.metadata {
+            args.extend(tags.options());
+        }
+
+        args.extend(playlist_options(
+            job.is_playlist,
+            job.download_folder.clone(),
+        ));
+
+        // multiple filters match any of them
+        for id in &job.only_ids {
+            args.push(String::from("--match-filters"));
+            args.push(format!("id='{id}'"));
+        }
+
+        match job.sponsorblock {
+            SponsorBlockOption::Disabled => {}
+            SponsorBlockOption::Remove => {
+                args.push(String::from("--sponsorblock-remove=default"));
+            }
+            SponsorBlockOption::Mark => {
+                args.push(String::from("--sponsorblock-mark=default"));
+            }
+        }
+
+        args
+    }
+
+    pub fn cookies_args(&self) -> Vec<String> {
+        match self.config.cookies_source {
+            CookiesSource::File => match &self.config.cookies_file {
+                Some(cookies_file) => vec![
+                    String::from("--cookies"),
+                    cookies_file.to_string_lossy().to_string(),
+                ],
+                None => Vec::new(),
+            },
+            CookiesSource::Browser => vec![
+                String::from("--cookies-from-browser"),
+                self.config.browser_cookies.options(),
+            ],
+        }
+    }
+
+    /// Asks yt-dlp to stop recording the livestream and finish writing the file,
+    /// [`Message::DownloadProcessExited`](crate::Message::DownloadProcessExited) ends the download after that
+    pub fn stop_recording(&mut self) -> bool {
+        let Some(recording) = self.recording.as_mut() else {
+            return false;
+        };
+
+        if recording.stopping {
+            return true;
+        }
+
+        if recording.started.is_none() || !self.command.interrupt() {
+            return false;
+        }
+
+        recording.stopping = true;
+        self.download_message = Some(Ok(fl!("finishing_recording")));
+
+        true
+    }
+
+    /// Refreshes the recording status and stops the recording after its maximum duration
+    pub fn update_recording(&mut self) {
+        let Some(recording) = &self.recording else {
+            return;
+        };
+
+        if recording.stopping {
+            return;
+        }
+
+        let max_duration = self
+            .current_job
+            .as_ref()
+            .and_then(|job| job.live)
+            .and_then(|live| live.max_duration());
+
+        if let Some((elapsed, max_duration)) = recording.elapsed().zip(max_duration)
+            && elapsed >= max_duration
+        {
+            tracing::info!("stopping the recording after {max_duration:?}");
+
+            if !self.stop_recording() {
+                self.end_download(Some(Ok(fl!("recording_saved"))));
+            }
+
+            return;
+        }
+
+        if let Some(status) = recording.status() {
+            self.download_message = Some(Ok(status));
+        }
+    }
+}
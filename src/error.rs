@@ -1,11 +1,22 @@
 use std::path::PathBuf;
 
-use crate::progress::ProgressError;
+use crate::{
+    format_selector::FormatError, media_options::LoudnessError, metadata::TagError,
+    progress::ProgressError, sections::SectionError,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum DownloadError {
     #[error(transparent)]
     Progress(ProgressError),
+    #[error(transparent)]
+    Section(SectionError),
+    #[error(transparent)]
+    Tags(TagError),
+    #[error(transparent)]
+    Format(FormatError),
+    #[error(transparent)]
+    Loudness(LoudnessError),
     #[error("invalid URL on position: {0}")]
     InvalidURL(usize),
     #[error(r#"Directory "{0}" does not exist, please create it then start the download"#)]
@@ -14,6 +25,8 @@ pub enum DownloadError {
     NoDownloadURL,
     #[error("yt-dlp binary is missing")]
     YtDlpMissing,
+    #[error("yt-dlp exited unexpectedly")]
+    Exited,
     #[error("Something went wrong, logging...")]
     Other,
 }
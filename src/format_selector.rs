@@ -0,0 +1,835 @@
+//! Parser for yt-dlp's format selection (`-f`) and sorting (`-S`) expressions.
+//!
+//! The expressions are validated before starting a download so mistakes are
+//! reported with a useful message instead of yt-dlp's "Invalid format specification".
+//!
+//! reference: https://github.com/yt-dlp/yt-dlp#format-selection
+
+use serde::{Deserialize, Serialize};
+
+use crate::media_options::Options;
+
+/// Fields that are compared as numbers in filters
+const NUMERIC_FIELDS: [&str; 11] = [
+    "width",
+    "height",
+    "aspect_ratio",
+    "tbr",
+    "abr",
+    "vbr",
+    "asr",
+    "fps",
+    "audio_channels",
+    "filesize",
+    "filesize_approx",
+];
+
+/// Common fields that are compared as text in filters, other fields are allowed too
+const STRING_FIELDS: [&str; 9] = [
+    "ext",
+    "acodec",
+    "vcodec",
+    "container",
+    "protocol",
+    "format_id",
+    "language",
+    "format_note",
+    "dynamic_range",
+];
+
+const SORT_FIELDS: [&str; 30] = [
+    "hasvid",
+    "hasaud",
+    "ie_pref",
+    "lang",
+    "quality",
+    "source",
+    "proto",
+    "vcodec",
+    "acodec",
+    "codec",
+    "vext",
+    "aext",
+    "ext",
+    "filesize",
+    "fs_approx",
+    "size",
+    "height",
+    "width",
+    "res",
+    "fps",
+    "hdr",
+    "channels",
+    "tbr",
+    "vbr",
+    "abr",
+    "br",
+    "asr",
+    "id",
+    "hidden",
+    "aud_or_vid",
+];
+
+/// A custom format selector and sort order, empty values aren't used
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CustomFormat {
+    pub selector: String,
+    pub sort: String,
+}
+
+impl CustomFormat {
+    /// The `-f` and `-S` arguments of a video download with `options`,
+    /// the options' selector is used when there's no custom one
+    pub fn options(&self, options: &Options) -> Vec<String> {
+        let mut args = Vec::new();
+
+        let selector = match self.selector.trim() {
+            "" => options.format_selector(),
+            selector => Some(selector.to_string()),
+        };
+        if let Some(selector) = selector {
+            args.push(String::from("-f"));
+            args.push(selector);
+        }
+
+        // the custom sort fields take precedence over the resolution
+        args.push(String::from("-S"));
+        args.push(match self.sort.trim() {
+            "" => options.format_sort(),
+            sort => format!("{sort},{}", options.format_sort()),
+        });
+
+        args
+    }
+
+    pub fn validate(&self) -> Result<(), FormatError> {
+        if !self.selector.trim().is_empty() {
+            parse_selector(&self.selector)?;
+        }
+
+        parse_sort(&self.sort)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// `b`: has both video and audio
+    Combined,
+    /// `b*`: has video or audio
+    Any,
+    /// `bv`: video only
+    Video,
+    /// `bv*`: has video, may have audio
+    VideoAny,
+    /// `ba`: audio only
+    Audio,
+    /// `ba*`: has audio, may have video
+    AudioAny,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Spec {
+    /// `Best(kind, n)` is the nth best format, starting from 1
+    Best(Kind, u32),
+    Worst(Kind, u32),
+    All,
+    MergeAll,
+    /// A format id or an extension
+    FormatId(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Eq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    StartsWith,
+    EndsWith,
+    Contains,
+    Matches,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    pub field: String,
+    pub op: Op,
+    /// `!=`, `!^=`, ...
+    pub negate: bool,
+    /// `?` after the operator also matches formats where the field is missing
+    pub allow_missing: bool,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selector {
+    Single {
+        spec: Spec,
+        filters: Vec<Filter>,
+    },
+    Group(Box<Selector>, Vec<Filter>),
+    /// `a+b`
+    Merge(Vec<Selector>),
+    /// `a/b`, the first one that's available
+    Alternatives(Vec<Selector>),
+    /// `a,b`, downloads all of them
+    Multiple(Vec<Selector>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SortField {
+    pub field: String,
+    /// `+` prefix
+    pub reverse: bool,
+    /// `:limit` or `~limit` for the closest value
+    pub limit: Option<String>,
+    pub closest: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FormatError {
+    #[error("the format selector is empty")]
+    Empty,
+    #[error("unexpected \"{found}\" at position {position}")]
+    Unexpected { found: char, position: usize },
+    #[error("unexpected end of the format selector, expected {expected}")]
+    UnexpectedEnd { expected: &'static str },
+    #[error("\"{0}\" is a format id, it can't have a * or an index")]
+    InvalidModifier(String),
+    #[error("invalid filter \"[{filter}]\": {reason}")]
+    InvalidFilter { filter: String, reason: String },
+    #[error("unknown sort field \"{field}\"{suggestion}")]
+    UnknownSortField { field: String, suggestion: String },
+    #[error("invalid sort field \"{0}\", use [+]FIELD[:LIMIT] e.g. res:1080")]
+    InvalidSortField(String),
+}
+
+/// Returns ` (did you mean "..."?)` when `field` is close to one of `known`
+fn suggestion(field: &str, known: &[&str]) -> String {
+    known
+        .iter()
+        .map(|known| (edit_distance(field, known), known))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| format!(" (did you mean \"{known}\"?)"))
+        .unwrap_or_default()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+struct Parser<'a> {
+    chars: Vec<char>,
+    position: usize,
+    input: &'a str,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+
+        if self.peek() == Some(c) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn unexpected(&self, expected: &'static str) -> FormatError {
+        match self.peek() {
+            Some(found) => FormatError::Unexpected {
+                found,
+                position: self.position + 1,
+            },
+            None => FormatError::UnexpectedEnd { expected },
+        }
+    }
+
+    fn list(
+        &mut self,
+        separator: char,
+        item: fn(&mut Self) -> Result<Selector, FormatError>,
+        wrap: fn(Vec<Selector>) -> Selector,
+    ) -> Result<Selector, FormatError> {
+        let mut items = vec![item(self)?];
+
+        while self.eat(separator) {
+            items.push(item(self)?);
+        }
+
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            wrap(items)
+        })
+    }
+
+    fn multiple(&mut self) -> Result<Selector, FormatError> {
+        self.list(',', Self::alternatives, Selector::Multiple)
+    }
+
+    fn alternatives(&mut self) -> Result<Selector, FormatError> {
+        self.list('/', Self::merge, Selector::Alternatives)
+    }
+
+    fn merge(&mut self) -> Result<Selector, FormatError> {
+        self.list('+', Self::atom, Selector::Merge)
+    }
+
+    fn atom(&mut self) -> Result<Selector, FormatError> {
+        if self.eat('(') {
+            let selector = self.multiple()?;
+
+            if !self.eat(')') {
+                return Err(self.unexpected("\")\""));
+            }
+
+            let filters = self.filters()?;
+            return Ok(Selector::Group(Box::new(selector), filters));
+        }
+
+        self.skip_whitespace();
+
+        let start = self.position;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            self.position += 1;
+        }
+
+        let name: String = self.chars[start..self.position].iter().collect();
+
+        if name.is_empty() {
+            return Err(self.unexpected("a format like bv or a format id"));
+        }
+
+        let any = self.peek() == Some('*');
+        if any {
+            self.position += 1;
+        }
+
+        let index = if self.peek() == Some('.') {
+            self.position += 1;
+
+            let start = self.position;
+            while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                self.position += 1;
+            }
+
+            let index: String = self.chars[start..self.position].iter().collect();
+            match index.parse::<u32>() {
+                Ok(index) if index > 0 => Some(index),
+                _ => return Err(self.unexpected("an index like bv.2")),
+            }
+        } else {
+            None
+        };
+
+        let kind = |combined, video, audio| {
+            Some(match (name.as_str(), any) {
+                (n, false) if n == combined => Kind::Combined,
+                (n, true) if n == combined => Kind::Any,
+                (n, false) if n == video => Kind::Video,
+                (n, true) if n == video => Kind::VideoAny,
+                (n, false) if n == audio => Kind::Audio,
+                (n, true) if n == audio => Kind::AudioAny,
+                _ => return None,
+            })
+        };
+
+        let spec = if let Some(kind) =
+            kind("b", "bv", "ba").or_else(|| kind("best", "bestvideo", "bestaudio"))
+        {
+            Spec::Best(kind, index.unwrap_or(1))
+        } else if let Some(kind) =
+            kind("w", "wv", "wa").or_else(|| kind("worst", "worstvideo", "worstaudio"))
+        {
+            Spec::Worst(kind, index.unwrap_or(1))
+        } else if any || index.is_some() {
+            return Err(FormatError::InvalidModifier(name));
+        } else if name == "all" {
+            Spec::All
+        } else if name == "mergeall" {
+            Spec::MergeAll
+        } else {
+            Spec::FormatId(name)
+        };
+
+        let filters = self.filters()?;
+
+        Ok(Selector::Single { spec, filters })
+    }
+
+    fn filters(&mut self) -> Result<Vec<Filter>, FormatError> {
+        let mut filters = Vec::new();
+
+        // filters have to follow the format directly
+        while self.peek() == Some('[') {
+            let start = self.position + 1;
+            let mut quote = None;
+
+            loop {
+                self.position += 1;
+
+                match (self.peek(), quote) {
+                    (None, _) => return Err(FormatError::UnexpectedEnd { expected: "\"]\"" }),
+                    (Some(c), Some(q)) if c == q => quote = None,
+                    (Some(']'), None) => break,
+                    (Some(c @ ('"' | '\'')), None) => quote = Some(c),
+                    _ => {}
+                }
+            }
+
+            let filter: String = self.chars[start..self.position].iter().collect();
+            self.position += 1;
+
+            filters.push(parse_filter(&filter)?);
+        }
+
+        Ok(filters)
+    }
+}
+
+fn parse_filter(filter: &str) -> Result<Filter, FormatError> {
+    let invalid = |reason: String| FormatError::InvalidFilter {
+        filter: filter.to_string(),
+        reason,
+    };
+
+    let trimmed = filter.trim();
+
+    let field_end = trimmed
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-'))
+        .unwrap_or(trimmed.len());
+    let (field, rest) = trimmed.split_at(field_end);
+
+    if field.is_empty() {
+        return Err(invalid(String::from("missing the field name")));
+    }
+
+    let rest = rest.trim_start();
+    let (negate, rest) = match rest.strip_prefix('!') {
+        Some(rest) if !rest.starts_with('=') => (true, rest),
+        _ => (false, rest),
+    };
+
+    let operators = [
+        ("!=", Op::Eq),
+        ("<=", Op::Le),
+        (">=", Op::Ge),
+        ("^=", Op::StartsWith),
+        ("$=", Op::EndsWith),
+        ("*=", Op::Contains),
+        ("~=", Op::Matches),
+        ("=", Op::Eq),
+        ("<", Op::Lt),
+        (">", Op::Gt),
+    ];
+
+    let Some((operator, op)) = operators
+        .into_iter()
+        .find(|(operator, _)| rest.starts_with(operator))
+    else {
+        return Err(invalid(String::from(
+            "missing the operator, use =, !=, <, <=, >, >=, ^=, $=, *= or ~=",
+        )));
+    };
+
+    let negate = negate || operator == "!=";
+    let rest = &rest[operator.len()..];
+
+    let (allow_missing, value) = match rest.strip_prefix('?') {
+        Some(value) => (true, value.trim()),
+        None => (false, rest.trim()),
+    };
+
+    let value = value
+        .strip_prefix(['"', '\''])
+        .and_then(|v| v.strip_suffix(['"', '\'']))
+        .unwrap_or(value);
+
+    if value.is_empty() {
+        return Err(invalid(String::from("missing the value")));
+    }
+
+    let is_numeric = NUMERIC_FIELDS.contains(&field);
+    let is_comparison = matches!(op, Op::Lt | Op::Le | Op::Gt | Op::Ge);
+
+    let value = if is_numeric
+        && !matches!(
+            op,
+            Op::StartsWith | Op::EndsWith | Op::Contains | Op::Matches
+        ) {
+        let number = if field.starts_with("filesize") {
+            parse_filesize(value)
+        } else {
+            value.parse().ok()
+        };
+
+        Value::Number(number.ok_or_else(|| {
+            invalid(format!(
+                "\"{field}\" is compared to a number, \"{value}\" isn't one"
+            ))
+        })?)
+    } else if is_comparison {
+        return Err(invalid(format!(
+            "\"{operator}\" only works on numeric fields like height or fps{}",
+            suggestion(field, &NUMERIC_FIELDS)
+        )));
+    } else if is_numeric {
+        return Err(invalid(format!(
+            "\"{operator}\" only works on text fields like ext or vcodec"
+        )));
+    } else if let Some(typo) = typo(field) {
+        // other fields are allowed, but a field that's close to a known one is likely a typo
+        return Err(invalid(format!("unknown field \"{field}\"{typo}")));
+    } else {
+        Value::Text(value.to_string())
+    };
+
+    if negate && !matches!(value, Value::Text(_)) && operator != "!=" {
+        return Err(invalid(String::from(
+            "only text comparisons can be negated with !",
+        )));
+    }
+
+    Ok(Filter {
+        field: field.to_string(),
+        op,
+        negate,
+        allow_missing,
+        value,
+    })
+}
+
+/// The suggestion for a text field that isn't known but is close to a known field
+fn typo(field: &str) -> Option<String> {
+    if STRING_FIELDS.contains(&field) {
+        return None;
+    }
+
+    let known: Vec<&str> = STRING_FIELDS.into_iter().chain(NUMERIC_FIELDS).collect();
+    Some(suggestion(field, &known)).filter(|suggestion| !suggestion.is_empty())
+}
+
+/// Parses sizes like `100M`, `1.5GiB` or `500KB` into bytes
+fn parse_filesize(size: &str) -> Option<f64> {
+    let number_end = size
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(number_end);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier = match unit.trim_end_matches(['B', 'b']).trim_end_matches('i') {
+        "" => 1.,
+        "k" | "K" => 1024.,
+        "m" | "M" => 1024_f64.powi(2),
+        "g" | "G" => 1024_f64.powi(3),
+        "t" | "T" => 1024_f64.powi(4),
+        _ => return None,
+    };
+
+    Some(number * multiplier)
+}
+
+pub fn parse_selector(input: &str) -> Result<Selector, FormatError> {
+    if input.trim().is_empty() {
+        return Err(FormatError::Empty);
+    }
+
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        position: 0,
+        input,
+    };
+
+    let selector = parser.multiple()?;
+
+    parser.skip_whitespace();
+    if parser.peek().is_some() {
+        return Err(parser.unexpected("the end"));
+    }
+
+    tracing::debug!("parsed format selector {:?}: {selector:?}", parser.input);
+
+    Ok(selector)
+}
+
+/// Parses a comma separated sort order like `vcodec:avc1,res:1080,+size`
+pub fn parse_sort(input: &str) -> Result<Vec<SortField>, FormatError> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(|sort_field| {
+            let (reverse, rest) = match sort_field.strip_prefix('+') {
+                Some(rest) => (true, rest),
+                None => (false, sort_field),
+            };
+
+            let (field, limit, closest) = match rest.find([':', '~']) {
+                Some(i) => (&rest[..i], Some(&rest[i + 1..]), rest[i..].starts_with('~')),
+                None => (rest, None, false),
+            };
+
+            if field.is_empty() || limit.is_some_and(str::is_empty) {
+                return Err(FormatError::InvalidSortField(sort_field.to_string()));
+            }
+
+            if !SORT_FIELDS.contains(&field) {
+                return Err(FormatError::UnknownSortField {
+                    field: field.to_string(),
+                    suggestion: suggestion(field, &SORT_FIELDS),
+                });
+            }
+
+            Ok(SortField {
+                field: field.to_string(),
+                reverse,
+                limit: limit.map(ToString::to_string),
+                closest,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(field: &str, op: Op, value: Value) -> Filter {
+        Filter {
+            field: field.to_string(),
+            op,
+            negate: false,
+            allow_missing: false,
+            value,
+        }
+    }
+
+    #[test]
+    fn test_parse_selector() {
+        assert_eq!(
+            parse_selector("bv*[vcodec^=avc1][height<=1080]+ba[ext=m4a]/b"),
+            Ok(Selector::Alternatives(vec![
+                Selector::Merge(vec![
+                    Selector::Single {
+                        spec: Spec::Best(Kind::VideoAny, 1),
+                        filters: vec![
+                            filter("vcodec", Op::StartsWith, Value::Text(String::from("avc1"))),
+                            filter("height", Op::Le, Value::Number(1080.)),
+                        ],
+                    },
+                    Selector::Single {
+                        spec: Spec::Best(Kind::Audio, 1),
+                        filters: vec![filter("ext", Op::Eq, Value::Text(String::from("m4a")))],
+                    },
+                ]),
+                Selector::Single {
+                    spec: Spec::Best(Kind::Combined, 1),
+                    filters: Vec::new(),
+                },
+            ]))
+        );
+
+        assert_eq!(
+            parse_selector("(137/bv.2)[filesize<?100M], 140"),
+            Ok(Selector::Multiple(vec![
+                Selector::Group(
+                    Box::new(Selector::Alternatives(vec![
+                        Selector::Single {
+                            spec: Spec::FormatId(String::from("137")),
+                            filters: Vec::new(),
+                        },
+                        Selector::Single {
+                            spec: Spec::Best(Kind::Video, 2),
+                            filters: Vec::new(),
+                        },
+                    ])),
+                    vec![Filter {
+                        allow_missing: true,
+                        ..filter("filesize", Op::Lt, Value::Number(100. * 1024_f64.powi(2)))
+                    }],
+                ),
+                Selector::Single {
+                    spec: Spec::FormatId(String::from("140")),
+                    filters: Vec::new(),
+                },
+            ]))
+        );
+
+        assert_eq!(
+            parse_selector("bv[vcodec!^=av01]"),
+            Ok(Selector::Single {
+                spec: Spec::Best(Kind::Video, 1),
+                filters: vec![Filter {
+                    negate: true,
+                    ..filter("vcodec", Op::StartsWith, Value::Text(String::from("av01")))
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_selector_errors() {
+        assert_eq!(parse_selector(" "), Err(FormatError::Empty));
+        assert_eq!(
+            parse_selector("bv+"),
+            Err(FormatError::UnexpectedEnd {
+                expected: "a format like bv or a format id"
+            })
+        );
+        assert_eq!(
+            parse_selector("bv+ba)"),
+            Err(FormatError::Unexpected {
+                found: ')',
+                position: 6
+            })
+        );
+        assert_eq!(
+            parse_selector("(bv+ba"),
+            Err(FormatError::UnexpectedEnd { expected: "\")\"" })
+        );
+        assert_eq!(
+            parse_selector("137*"),
+            Err(FormatError::InvalidModifier(String::from("137")))
+        );
+        assert_eq!(
+            parse_selector("bv[height<=1080"),
+            Err(FormatError::UnexpectedEnd { expected: "\"]\"" })
+        );
+        assert_eq!(
+            parse_selector("bv[heigth<=1080]"),
+            Err(FormatError::InvalidFilter {
+                filter: String::from("heigth<=1080"),
+                reason: String::from(
+                    "\"<=\" only works on numeric fields like height or fps (did you mean \"height\"?)"
+                ),
+            })
+        );
+        assert_eq!(
+            parse_selector("bv[height<=hd]"),
+            Err(FormatError::InvalidFilter {
+                filter: String::from("height<=hd"),
+                reason: String::from("\"height\" is compared to a number, \"hd\" isn't one"),
+            })
+        );
+        assert_eq!(
+            parse_selector("bv[vcodek^=avc1]"),
+            Err(FormatError::InvalidFilter {
+                filter: String::from("vcodek^=avc1"),
+                reason: String::from("unknown field \"vcodek\" (did you mean \"vcodec\"?)"),
+            })
+        );
+        assert!(matches!(
+            parse_selector("bv[height]"),
+            Err(FormatError::InvalidFilter { .. })
+        ));
+    }
+
+    #[test]
+    fn test_custom_format_options() {
+        let options = Options::default();
+
+        assert_eq!(
+            CustomFormat::default().options(&options),
+            vec![String::from("-S"), String::from("res:1080")]
+        );
+        assert_eq!(
+            CustomFormat {
+                selector: String::from("bv*+ba/b"),
+                sort: String::from("vcodec:av01"),
+            }
+            .options(&options),
+            vec![
+                String::from("-f"),
+                String::from("bv*+ba/b"),
+                String::from("-S"),
+                String::from("vcodec:av01,res:1080"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_sort() {
+        assert_eq!(
+            parse_sort("vcodec:avc1, +size,res~720"),
+            Ok(vec![
+                SortField {
+                    field: String::from("vcodec"),
+                    reverse: false,
+                    limit: Some(String::from("avc1")),
+                    closest: false,
+                },
+                SortField {
+                    field: String::from("size"),
+                    reverse: true,
+                    limit: None,
+                    closest: false,
+                },
+                SortField {
+                    field: String::from("res"),
+                    reverse: false,
+                    limit: Some(String::from("720")),
+                    closest: true,
+                },
+            ])
+        );
+        assert_eq!(parse_sort(""), Ok(Vec::new()));
+        assert_eq!(
+            parse_sort("resolution:1080"),
+            Err(FormatError::UnknownSortField {
+                field: String::from("resolution"),
+                suggestion: String::new(),
+            })
+        );
+        assert_eq!(
+            parse_sort("vcodek"),
+            Err(FormatError::UnknownSortField {
+                field: String::from("vcodek"),
+                suggestion: String::from(" (did you mean \"vcodec\"?)"),
+            })
+        );
+        assert_eq!(
+            parse_sort("res:"),
+            Err(FormatError::InvalidSortField(String::from("res:")))
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Finished downloads and the files they produced, saved to disk so the files
+//! can be opened from the History tab after a restart.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use iced::widget::text;
+
+use crate::{
+    Message, YtGUI, app::SPACING, download::DownloadJob, fl, i18n::dir_row, theme::button,
+};
+
+/// Older entries are dropped
+const MAX_ENTRIES: usize = 200;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HistoryEntry {
+    /// The job with the files it wrote
+    pub job: DownloadJob,
+    /// Unix timestamp in seconds
+    pub finished_at: i64,
+    /// Set when the download failed
+    pub error: Option<String>,
+}
+
+impl HistoryEntry {
+    pub fn finish_time(&self) -> DateTime<Local> {
+        Local
+            .timestamp_opt(self.finished_at, 0)
+            .single()
+            .unwrap_or_else(Local::now)
+    }
+}
+
+fn history_path() -> PathBuf {
+    dirs::data_dir()
+        .expect("data directory")
+        .join("ytdlp-gui/history.json")
+}
+
+pub fn load() -> Vec<HistoryEntry> {
+    match fs::read_to_string(history_path()) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::error!("failed to parse download history: {e}");
+            Vec::new()
+        }),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => {
+            tracing::error!("failed to read download history: {e}");
+            Vec::new()
+        }
+    }
+}
+
+pub fn save(history: &[HistoryEntry]) -> io::Result<()> {
+    let path = history_path();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, serde_json::to_string_pretty(history)?)
+}
+
+pub fn open_file(path: &Path) {
+    if let Err(e) = open::that(path) {
+        tracing::error!("failed to open {}: {e}", path.display());
+    }
+}
+
+/// Opens the folder of `path` with the file selected where the platform supports it
+pub fn show_in_folder(path: &Path) {
+    #[cfg(target_os = "windows")]
+    let result = {
+        use std::os::windows::process::CommandExt;
+
+        // explorer doesn't understand the argument when the whole of it is quoted,
+        // which `arg` does for paths with spaces
+        std::process::Command::new("explorer")
+            .raw_arg(format!("/select,\"{}\"", path.display()))
+            .spawn()
+            .map(|_| ())
+    };
+
+    #[cfg(target_os = "macos")]
+    let result = std::process::Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .spawn()
+        .map(|_| ());
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let result = open::that(path.parent().unwrap_or(path));
+
+    if let Err(e) = result {
+        tracing::error!("failed to show {} in its folder: {e}", path.display());
+    }
+}
+
+/// The file name with "Open file" and "Show in folder" buttons
+pub fn file_actions(path: &Path) -> iced::widget::Row<'static, Message> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string());
+
+    dir_row(vec![
+        text(name).size(14).width(iced::Length::Fill).into(),
+        button(text(fl!("open_file")).size(14))
+            .on_press(Message::OpenFile(path.to_path_buf()))
+            .into(),
+        button(text(fl!("show_in_folder")).size(14))
+            .on_press(Message::ShowInFolder(path.to_path_buf()))
+            .into(),
+    ])
+    .spacing(SPACING)
+    .align_y(iced::Alignment::Center)
+}
+
+impl YtGUI {
+    pub fn record_history(&mut self, job: DownloadJob, error: Option<String>) {
+        self.history.push(HistoryEntry {
+            job,
+            finished_at: Local::now().timestamp(),
+            error,
+        });
+
+        if self.history.len() > MAX_ENTRIES {
+            let excess = self.history.len() - MAX_ENTRIES;
+            self.history.drain(..excess);
+        }
+
+        self.save_history();
+    }
+
+    pub fn save_history(&self) {
+        if let Err(e) = save(&self.history) {
+            tracing::error!("failed to save download history: {e}");
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Commands run after each downloaded video, like moving the file into a media
+//! server library or starting a transcoding script.
+//!
+//! The commands run in the shell with `{filepath}`, `{title}`, `{url}` and `{preset}`
+//! replaced by quoted values, their output goes to the app's log with the download's other output
+//! and is appended to `hooks.log` in the cache directory.
+
+use std::{io::Write, path::PathBuf, process::Stdio};
+
+use chrono::Local;
+use iced::futures::channel::mpsc::UnboundedSender;
+
+use crate::Message;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// The video a hook runs for
+#[derive(Debug, Clone, Default)]
+pub struct DownloadedFile {
+    pub filepath: PathBuf,
+    pub title: String,
+    pub url: String,
+    pub preset: Option<String>,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum HookError {
+    #[error("\"{command}\" couldn't be started: {error}")]
+    Spawn { command: String, error: String },
+    #[error("\"{command}\" exited with {status}: {output}")]
+    Failed {
+        command: String,
+        status: String,
+        output: String,
+    },
+}
+
+/// Quotes `value` for the shell the hooks run in
+fn quote(value: &str) -> String {
+    if cfg!(target_os = "windows") {
+        quote_cmd(value)
+    } else {
+        quote_sh(value)
+    }
+}
+
+fn quote_sh(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r#"'"'"'"#))
+}
+
+/// `%` is escaped outside of the quotes since cmd expands `%VAR%` inside them
+fn quote_cmd(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\"").replace('%', "\"^%\""))
+}
+
+/// Replaces the placeholders of `command` with the values of `file`.
+///
+/// It's done in one pass so placeholders in the values (e.g. a title with `{title}` in it) stay as they are
+pub fn expand(command: &str, file: &DownloadedFile) -> String {
+    let filepath = file.filepath.to_string_lossy();
+    let placeholders = [
+        ("{filepath}", filepath.as_ref()),
+        ("{title}", file.title.as_str()),
+        ("{url}", file.url.as_str()),
+        ("{preset}", file.preset.as_deref().unwrap_or_default()),
+    ];
+
+    let mut expanded = String::with_capacity(command.len());
+    let mut rest = command;
+
+    while !rest.is_empty() {
+        match placeholders
+            .iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            Some((placeholder, value)) => {
+                expanded.push_str(&quote(value));
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                let c = rest.chars().next().expect("rest isn't empty");
+                expanded.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    expanded
+}
+
+/// Runs the hooks one after the other in the background, a `HookFinished` message
+/// is sent for each of them
+pub fn run_hooks(hooks: Vec<String>, file: DownloadedFile, sender: UnboundedSender<Message>) {
+    std::thread::spawn(move || {
+        for hook in hooks.iter().filter(|hook| !hook.trim().is_empty()) {
+            let result = run_hook(&expand(hook, &file), &file.url);
+
+            if let Err(e) = &result {
+                tracing::error!("post-download hook failed: {e}");
+            }
+
+            let _ = sender.unbounded_send(Message::HookFinished(result));
+        }
+    });
+}
+
+fn run_hook(command: &str, url: &str) -> Result<(), HookError> {
+    tracing::info!("running post-download hook for {url}: {command}");
+
+    #[cfg(target_os = "windows")]
+    let mut shell = {
+        use std::os::windows::process::CommandExt;
+
+        let mut shell = std::process::Command::new("cmd");
+        shell.creation_flags(CREATE_NO_WINDOW);
+        // passed as is, `arg` would escape the quotes in a way cmd doesn't understand,
+        // `/S` makes cmd always strip the outer quotes and keep the ones of the placeholders
+        shell.raw_arg("/S /C").raw_arg(format!("\"{command}\""));
+        shell
+    };
+
+    #[cfg(not(target_os = "windows"))]
+    let mut shell = {
+        let mut shell = std::process::Command::new("sh");
+        shell.arg("-c").arg(command);
+        shell
+    };
+
+    let output = shell
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| HookError::Spawn {
+            command: command.to_string(),
+            error: e.to_string(),
+        })?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    for line in stdout.lines().chain(stderr.lines()) {
+        tracing::info!("post-download hook output for {url}: {line}");
+    }
+
+    log_output(command, &output.status.to_string(), &stdout, &stderr);
+
+    if !output.status.success() {
+        return Err(HookError::Failed {
+            command: command.to_string(),
+            status: output.status.to_string(),
+            output: stderr
+                .lines()
+                .chain(stdout.lines())
+                .rfind(|line| !line.trim().is_empty())
+                .unwrap_or_default()
+                .to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+fn log_output(command: &str, status: &str, stdout: &str, stderr: &str) {
+    let path = dirs::cache_dir()
+        .unwrap_or_default()
+        .join("ytdlp-gui")
+        .join("hooks.log");
+
+    let result = std::fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(&path)
+        .and_then(|mut file| {
+            writeln!(
+                file,
+                "[{}] {command} ({status})\n{stdout}{stderr}",
+                Local::now()
+            )
+        });
+
+    if let Err(e) = result {
+        tracing::error!("failed to write the hook output to {}: {e}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_cmd() {
+        assert_eq!(quote_cmd(r#"Say "Hi""#), r#""Say ""Hi""""#);
+        assert_eq!(quote_cmd("100%PATH%"), r#""100"^%"PATH"^%"""#);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_expand() {
+        let file = DownloadedFile {
+            filepath: PathBuf::from("/downloads/Don't Stop.mp4"),
+            title: String::from("Don't Stop"),
+            url: String::from("https://example.com/watch?v=1"),
+            preset: None,
+        };
+
+        assert_eq!(
+            expand("mv {filepath} /media/ && echo {url} {preset}", &file),
+            r#"mv '/downloads/Don'"'"'t Stop.mp4' /media/ && echo 'https://example.com/watch?v=1' ''"#
+        );
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_expand_placeholder_in_title() {
+        let file = DownloadedFile {
+            filepath: PathBuf::from("/downloads/{title};touch pwned;.mp4"),
+            title: String::from("{title};touch pwned;"),
+            url: String::from("https://example.com/watch?v=1"),
+            preset: None,
+        };
+
+        assert_eq!(
+            expand("mv {filepath} /media/", &file),
+            "mv '/downloads/{title};touch pwned;.mp4' /media/"
+        );
+        assert_eq!(
+            expand("echo {title} {url}", &file),
+            "echo '{title};touch pwned;' 'https://example.com/watch?v=1'"
+        );
+    }
+}
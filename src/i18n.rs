@@ -36,6 +36,9 @@ pub static LANGUAGE_LOADER: LazyLock<FluentLanguageLoader> = LazyLock::new(|| {
         .load_fallback_language(&Localizations)
         .expect("Error while loading fallback language");
 
+    // the unicode isolation marks around arguments show up as boxes in iced
+    loader.set_use_isolating(false);
+
     loader
 });
 
@@ -1,9 +1,12 @@
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use std::{fs, io};
 
 use app::{DownloadType, Tab};
+use download::DownloadJob;
 use error::DownloadError;
 #[cfg(feature = "explain")]
 use iced::Color;
@@ -17,18 +20,58 @@ use serde::de::IntoDeserializer;
 use serde::{Deserialize, Serialize};
 
 mod app;
+mod chapters;
 mod checkbox;
 mod collapsible;
 pub mod command;
+pub mod cookies;
+mod credentials;
+mod download;
 mod error;
+mod format_selector;
+mod history;
+mod hooks;
 pub mod i18n;
+mod livestream;
 pub mod media_options;
+mod metadata;
+mod network;
+mod notifications;
+mod playlist_report;
+mod preset;
+mod probe;
 pub mod progress;
+mod redact;
+mod retry;
+mod scheduled;
+mod scheduler;
+mod sections;
 mod sponsorblock;
+mod subscriptions;
 pub mod theme;
+mod thumbnail;
 pub mod update;
-
+mod ytdlp_error;
+
+pub use thumbnail::{SQUARE_THUMBNAIL_ARG, crop_to_square};
+
+use chapters::{ChapterOptions, SplitChapters};
+use cookies::{Browser, BrowserCookies, CookiesCheck, CookiesSource, Keyring};
+use credentials::Credential;
+use format_selector::CustomFormat;
+use livestream::{LiveOptions, Recording};
+use metadata::{TagField, Tags};
+use network::{IpVersion, NetworkOptions};
+use notifications::{FinishedVideo, NotificationSettings};
+use preset::Preset;
+use probe::MediaInfo;
+use redact::{Redacting, Secret};
+use retry::{PendingRetry, RetryPolicy};
+use scheduled::ScheduledJob;
+use scheduler::BandwidthRule;
 use sponsorblock::SponsorBlockOption;
+use subscriptions::{ArchiveEntry, ChannelSubscription};
+use thumbnail::{ThumbnailFormat, ThumbnailOptions};
 use tracing::Level;
 use tracing::metadata::LevelFilter;
 use tracing_appender::rolling;
@@ -38,16 +81,35 @@ use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
 use crate::media_options::Options;
-use crate::media_options::{AudioFormat, AudioQuality, VideoFormat, VideoResolution};
+use crate::media_options::{
+    AudioBitrate, AudioChannels, AudioCodec, AudioFormat, AudioQuality, DynamicRange, MaxFps,
+    SampleRate, VideoCodec, VideoFormat, VideoResolution,
+};
 
 #[derive(Debug, Clone)]
 pub enum Message {
     InputChanged(String),
     TogglePlaylist(bool),
     ToggleThumbnail(bool),
+    ToggleWriteThumbnail(bool),
+    ToggleWriteAllThumbnails(bool),
+    SelectedThumbnailFormat(ThumbnailFormat),
+    ToggleSquareThumbnail(bool),
     SelectedSponsorBlockOption(SponsorBlockOption),
     SelectedVideoFormat(VideoFormat),
     SelectedResolution(VideoResolution),
+    SelectedVideoCodec(VideoCodec),
+    SelectedAudioCodec(AudioCodec),
+    SelectedMaxFps(MaxFps),
+    SelectedDynamicRange(DynamicRange),
+    ToggleStrictResolution(bool),
+    ToggleAudioOnlyFallback(bool),
+    SelectedAudioBitrate(AudioBitrate),
+    SelectedSampleRate(SampleRate),
+    SelectedAudioChannels(AudioChannels),
+    ToggleNormalizeLoudness(bool),
+    LoudnessTargetInput(String),
+    TruePeakInput(String),
     SelectedAudioFormat(AudioFormat),
     SelectedAudioQuality(AudioQuality),
     SelectDownloadFolder,
@@ -58,6 +120,56 @@ pub enum Message {
     ProgressEvent(String),
     StartDownload(String),
     StopDownload,
+    DownloadProcessExited(u32, bool),
+    ToggleLive(bool),
+    ToggleLiveFromStart(bool),
+    LiveWaitForVideoInput(String),
+    LiveMaxDurationInput(String),
+    LiveTick,
+    ToggleEmbedChapters(bool),
+    ToggleSplitChapters(bool),
+    ChapterTemplateInput(String),
+    ToggleEmbedMetadata(bool),
+    TagInput(TagField, String),
+    SectionsInput(String),
+    ChaptersInput(String),
+    ToggleForceKeyframes(bool),
+    FetchInfo,
+    InfoFetched(String, Result<MediaInfo, probe::Error>),
+    FormatSelectorInput(String),
+    FormatSortInput(String),
+    TestFormat,
+    FormatTested(Result<Vec<String>, probe::Error>),
+    ToggleDownloadLater,
+    StartTimeInput(String),
+    ScheduleDownload(String),
+    CancelScheduledJob(usize),
+    RemoveQueuedJob(usize),
+    ScheduledJobsTick,
+    SubscriptionUrlInput(String),
+    ToggleSkipExisting(bool),
+    AddSubscription,
+    RemoveSubscription(usize),
+    SelectedSubscriptionType(usize, DownloadType),
+    SelectedSubscriptionPreset(usize, String),
+    SubscriptionFolderInput(usize, String),
+    SubscriptionIntervalInput(String),
+    SyncSubscription(usize),
+    SyncSubscriptions,
+    SubscriptionSynced(String, Result<Vec<ArchiveEntry>, subscriptions::Error>),
+    AddHook,
+    RemoveHook(usize),
+    HookInput(usize, String),
+    HookFinished(Result<(), hooks::HookError>),
+    OpenFile(PathBuf),
+    ShowInFolder(PathBuf),
+    ClearHistory,
+    RetryDownload(Box<DownloadJob>),
+    NotificationSettingsChanged(NotificationSettings),
+    RetryTick,
+    ToggleRetry(bool),
+    MaxRetriesInput(String),
+    RetryDelayInput(String),
     IcedEvent(Event),
     ToggleSaveWindowPosition(bool),
     SelectYtDlpBinPath,
@@ -66,9 +178,37 @@ pub enum Message {
     SelectCookiesFile,
     SelectedCookiesFile(Option<PathBuf>),
     SelectCookiesFileTextInput(String),
+    SelectedCookiesSource(CookiesSource),
+    SelectedCookiesBrowser(Browser),
+    SelectedCookiesKeyring(Keyring),
+    CookiesProfileInput(String),
+    CookiesContainerInput(String),
+    CheckBrowserCookies,
+    BrowserCookiesChecked(Result<CookiesCheck, cookies::Error>),
+    AddCredential,
+    RemoveCredential(usize),
+    CredentialDomainInput(usize, String),
+    CredentialMachineInput(usize, String),
+    CredentialUsernameInput(usize, String),
+    CredentialPasswordInput(usize, Secret),
+    SelectedPreset(String),
+    ToggleNetworkOverride(bool),
+    NetworkProxyInput(String),
+    NetworkSourceAddressInput(String),
+    SelectedIpVersion(IpVersion),
+    AddBandwidthRule,
+    RemoveBandwidthRule(usize),
+    BandwidthRuleInput(usize, String),
+    SchedulerTick,
+    NetworkSocketTimeoutInput(String),
+    NetworkRetriesInput(String),
+    NetworkFragmentRetriesInput(String),
+    NetworkLimitRateInput(String),
     UpdateCheck(Result<Option<update::Version>, update::Error>),
     OpenLink(String),
     ToggleAdvancedOptions,
+    CheckConfigFile,
+    DismissOverwrittenSettings,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -87,6 +227,20 @@ pub struct WindowSize {
 pub struct Flags {
     pub url: Option<String>,
     pub config: Config,
+    /// Error from parsing the config file at startup, if any
+    pub config_error: Option<String>,
+}
+
+pub fn config_path() -> PathBuf {
+    dirs::config_dir()
+        .expect("config directory")
+        .join("ytdlp-gui/config.toml")
+}
+
+fn config_modified_time() -> Option<SystemTime> {
+    fs::metadata(config_path())
+        .and_then(|metadata| metadata.modified())
+        .ok()
 }
 
 fn download_folder_default() -> PathBuf {
@@ -115,10 +269,34 @@ pub struct Config {
     download_folder: PathBuf,
     #[serde(deserialize_with = "empty_string_as_none")]
     cookies_file: Option<PathBuf>,
+    cookies_source: CookiesSource,
+    browser_cookies: BrowserCookies,
+    credentials: Vec<Credential>,
     pub save_window_position: bool,
     pub window_position: Option<WindowPosition>,
     pub window_size: Option<WindowSize>,
     options: Options,
+    live: LiveOptions,
+    chapters: ChapterOptions,
+    thumbnails: ThumbnailOptions,
+    /// Free-form `-f` and `-S` for video downloads
+    custom_format: CustomFormat,
+    embed_metadata: bool,
+    network: NetworkOptions,
+    /// Named sets of download options, they're only defined in `config.toml`
+    presets: Vec<Preset>,
+    active_preset: Option<String>,
+    /// See [`scheduler`] for the format of the rules
+    bandwidth_schedule: Vec<String>,
+    subscriptions: Vec<ChannelSubscription>,
+    /// Minutes between subscription syncs
+    subscription_sync_interval: u32,
+    /// Shell commands run after each downloaded video, see [`hooks`]
+    post_download_hooks: Vec<String>,
+    /// Which events show a desktop notification
+    notifications: NotificationSettings,
+    /// Automatic retries of downloads that failed with a transient error
+    retry: RetryPolicy,
 }
 
 impl Default for Config {
@@ -127,29 +305,167 @@ impl Default for Config {
             bin_path: Default::default(),
             download_folder: download_folder_default(),
             cookies_file: Default::default(),
+            cookies_source: Default::default(),
+            browser_cookies: Default::default(),
+            credentials: Default::default(),
             save_window_position: Default::default(),
             window_position: Default::default(),
             window_size: Default::default(),
             options: Default::default(),
+            live: Default::default(),
+            chapters: Default::default(),
+            thumbnails: Default::default(),
+            custom_format: Default::default(),
+            embed_metadata: Default::default(),
+            network: Default::default(),
+            presets: Default::default(),
+            active_preset: Default::default(),
+            bandwidth_schedule: Default::default(),
+            subscriptions: Default::default(),
+            subscription_sync_interval: 60,
+            post_download_hooks: Default::default(),
+            notifications: Default::default(),
+            retry: Default::default(),
         }
     }
 }
 
 impl Config {
     fn update_config_file(&mut self) -> io::Result<()> {
+        self.register_secrets();
         let current_config = toml::to_string(self).expect("config to string");
-        let config_file = dirs::config_dir()
-            .expect("config directory")
-            .join("ytdlp-gui/config.toml");
-        fs::write(config_file, &current_config)?;
+        fs::write(config_path(), &current_config)?;
         tracing::info!("Updated config file to {}", current_config);
         Ok(())
     }
+
+    /// Registers the secrets in the config that aren't stored separately like the passwords
+    fn register_secrets(&self) {
+        self.network.register_secrets();
+
+        for network in self
+            .presets
+            .iter()
+            .filter_map(|preset| preset.network.as_ref())
+        {
+            network.register_secrets();
+        }
+    }
+
+    fn preset(&self, name: Option<&str>) -> Option<&Preset> {
+        let name = name?;
+        self.presets.iter().find(|preset| preset.name == name)
+    }
+
+    fn active_preset(&self) -> Option<&Preset> {
+        self.preset(self.active_preset.as_deref())
+    }
+
+    fn active_preset_mut(&mut self) -> Option<&mut Preset> {
+        let name = self.active_preset.as_ref()?;
+        self.presets.iter_mut().find(|preset| &preset.name == name)
+    }
+
+    /// The network options of `preset` if it overrides them, otherwise the global ones
+    fn network_options(&self, preset: Option<&str>) -> &NetworkOptions {
+        self.preset(preset)
+            .and_then(|preset| preset.network.as_ref())
+            .unwrap_or(&self.network)
+    }
+
+    fn network_options_mut(&mut self) -> &mut NetworkOptions {
+        let Config {
+            presets,
+            active_preset,
+            network,
+            ..
+        } = self;
+
+        active_preset
+            .as_ref()
+            .and_then(|name| presets.iter_mut().find(|preset| &preset.name == name))
+            .and_then(|preset| preset.network.as_mut())
+            .unwrap_or(network)
+    }
+
+    /// The proxy used for requests made by the app itself, like checking for updates
+    pub fn proxy(&self) -> Option<String> {
+        let proxy = self.network.proxy.trim();
+        (!proxy.is_empty()).then(|| proxy.to_string())
+    }
+
+    /// Takes the settings that were edited in the config file outside of the app,
+    /// `on_disk` is the file as it was last loaded or saved.
+    ///
+    /// Settings changed in the app that weren't saved yet are kept unless the same
+    /// setting was edited in the file too, the names of those are returned.
+    /// The window geometry is kept since it's tracked by the running window
+    fn merge(&mut self, on_disk: &Config, external: Config) -> Vec<String> {
+        let to_table = |config: &Config| match toml::Value::try_from(config) {
+            Ok(toml::Value::Table(table)) => table,
+            _ => toml::Table::new(),
+        };
+
+        let (merged, overwritten) =
+            merge_tables(&to_table(on_disk), to_table(self), to_table(&external));
+
+        match toml::Value::Table(merged).try_into::<Config>() {
+            Ok(merged) => *self = merged,
+            Err(e) => {
+                tracing::error!("failed to merge the config file changes: {e}");
+                *self = Config {
+                    window_position: self.window_position.take(),
+                    window_size: self.window_size,
+                    ..external
+                };
+            }
+        }
+
+        overwritten
+    }
+}
+
+/// Merges the top-level settings of an edited config file into the current ones,
+/// returns the merged settings and the names of the current ones that were overwritten
+fn merge_tables(
+    on_disk: &toml::Table,
+    mut current: toml::Table,
+    external: toml::Table,
+) -> (toml::Table, Vec<String>) {
+    let mut overwritten = Vec::new();
+
+    let keys: HashSet<String> = current.keys().chain(external.keys()).cloned().collect();
+
+    for key in keys {
+        if key == "window_position" || key == "window_size" {
+            continue;
+        }
+
+        let edited = external.get(&key) != on_disk.get(&key);
+        if !edited {
+            continue;
+        }
+
+        let changed_in_app = current.get(&key) != on_disk.get(&key);
+        if changed_in_app && current.get(&key) != external.get(&key) {
+            overwritten.push(key.clone());
+        }
+
+        match external.get(&key) {
+            Some(value) => current.insert(key, value.clone()),
+            None => current.remove(&key),
+        };
+    }
+
+    overwritten.sort();
+
+    (current, overwritten)
 }
 
 pub struct YtGUI {
     download_link: String,
     is_playlist: bool,
+    is_live: bool,
     get_thumbnail: bool,
     sponsorblock: SponsorBlockOption,
     config: Config,
@@ -158,6 +474,24 @@ pub struct YtGUI {
     download_type: DownloadType,
     playlist_progress: Option<String>,
     download_message: Option<Result<String, DownloadError>>,
+    /// Warning printed by yt-dlp that the user should know about, like a failed thumbnail embed
+    download_warning: Option<String>,
+    /// The postprocessing steps of the current download and how long they took
+    postprocessing_steps: Vec<progress::PostProcessingStep>,
+    /// Resolution of the last downloaded video, reported by yt-dlp
+    downloaded_resolution: Option<String>,
+    /// The last video moved to its final place, the hooks run for it
+    last_downloaded: Option<hooks::DownloadedFile>,
+    running_hooks: usize,
+    hook_errors: Vec<String>,
+    /// The videos of the current download, shown in the notification when it finishes
+    finished_videos: Vec<FinishedVideo>,
+    /// The failed download waiting to be started again
+    pending_retry: Option<PendingRetry>,
+    /// The skipped and failed items of the current or last playlist download
+    playlist_report: Option<playlist_report::PlaylistReport>,
+    /// How many times the current download was retried
+    retry_attempt: u32,
     is_file_dialog_open: bool,
     download_text_input_id: iced::widget::Id,
 
@@ -169,6 +503,43 @@ pub struct YtGUI {
     window_pos: Point,
     new_version: Option<update::Version>,
     show_advanced_options: bool,
+    config_modified: Option<SystemTime>,
+    /// The config file as it was last loaded or saved, to tell which settings were edited in it
+    config_on_disk: Config,
+    /// Settings changed in the app that were overwritten by an edit of the config file
+    overwritten_settings: Vec<String>,
+    config_error: Option<String>,
+    cookies_check: Option<Result<CookiesCheck, cookies::Error>>,
+    is_checking_cookies: bool,
+    queue: VecDeque<DownloadJob>,
+    current_job: Option<DownloadJob>,
+    recording: Option<Recording>,
+    split_chapters: Option<SplitChapters>,
+    tags: Tags,
+    /// Kept as text so partial numbers like "-" can be typed
+    loudness_target_input: String,
+    true_peak_input: String,
+    sections_input: String,
+    chapters_input: String,
+    force_keyframes: bool,
+    /// Metadata of `media_info_url` fetched with "Fetch info"
+    media_info: Option<Result<MediaInfo, probe::Error>>,
+    media_info_url: String,
+    is_fetching_info: bool,
+    /// Format ids the custom format resolved to with "Test"
+    format_test: Option<Result<Vec<String>, String>>,
+    is_testing_format: bool,
+    queue_paused: bool,
+    active_rule: Option<BandwidthRule>,
+    scheduled_jobs: Vec<ScheduledJob>,
+    history: Vec<history::HistoryEntry>,
+    show_download_later: bool,
+    start_time_input: String,
+    start_time_error: Option<String>,
+    subscription_url: String,
+    skip_existing: bool,
+    syncing_subscriptions: HashSet<String>,
+    subscription_errors: HashMap<String, String>,
 }
 
 impl YtGUI {
@@ -176,19 +547,36 @@ impl YtGUI {
         flags: Flags,
         progress_sender: iced::futures::channel::mpsc::UnboundedSender<Message>,
     ) -> Self {
+        flags.config.register_secrets();
         tracing::info!("config loaded: {flags:#?}");
 
+        let mut config = flags.config;
+        credentials::load_passwords(&mut config.credentials);
+        let loudness = config.options.loudness;
+        let config_on_disk = config.clone();
+
         Self {
             download_link: flags.url.clone().unwrap_or_default(),
             is_playlist: Default::default(),
+            is_live: Default::default(),
             get_thumbnail: Default::default(),
             sponsorblock: Default::default(),
-            config: flags.config,
+            config,
 
             active_tab: Tab::Video,
             download_type: DownloadType::Video,
             playlist_progress: None,
             download_message: Default::default(),
+            download_warning: None,
+            postprocessing_steps: Vec::new(),
+            downloaded_resolution: None,
+            last_downloaded: None,
+            running_hooks: 0,
+            hook_errors: Vec::new(),
+            finished_videos: Vec::new(),
+            pending_retry: None,
+            playlist_report: None,
+            retry_attempt: 0,
             download_text_input_id: iced::widget::Id::unique(),
 
             sender: progress_sender,
@@ -200,10 +588,96 @@ impl YtGUI {
             window_pos: Point::default(),
             new_version: None,
             show_advanced_options: false,
+            config_modified: config_modified_time(),
+            config_on_disk,
+            overwritten_settings: Vec::new(),
+            config_error: flags.config_error,
+            cookies_check: None,
+            is_checking_cookies: false,
+            queue: VecDeque::new(),
+            current_job: None,
+            recording: None,
+            split_chapters: None,
+            tags: Tags::default(),
+            loudness_target_input: loudness.target.to_string(),
+            true_peak_input: loudness.true_peak.to_string(),
+            sections_input: String::new(),
+            chapters_input: String::new(),
+            force_keyframes: false,
+            media_info: None,
+            media_info_url: String::new(),
+            is_fetching_info: false,
+            format_test: None,
+            is_testing_format: false,
+            queue_paused: false,
+            active_rule: None,
+            scheduled_jobs: scheduled::load(),
+            history: history::load(),
+            show_download_later: false,
+            start_time_input: String::new(),
+            start_time_error: None,
+            subscription_url: String::new(),
+            skip_existing: true,
+            syncing_subscriptions: HashSet::new(),
+            subscription_errors: HashMap::new(),
         }
     }
 
-    fn log_download(&self) {
+    /// Writes the config to disk, unless the file on disk has errors,
+    /// in which case it's left alone so the user's edits aren't lost
+    fn save_config(&mut self) -> io::Result<()> {
+        if let Some(e) = &self.config_error {
+            tracing::warn!("not overwriting config file because it failed to parse: {e}");
+            return Ok(());
+        }
+
+        self.config.update_config_file()?;
+        self.config_modified = config_modified_time();
+        self.config_on_disk = self.config.clone();
+
+        Ok(())
+    }
+
+    fn reload_config_if_changed(&mut self) {
+        let modified = config_modified_time();
+
+        if modified.is_none() || modified == self.config_modified {
+            return;
+        }
+
+        self.config_modified = modified;
+
+        let config_str = match fs::read_to_string(config_path()) {
+            Ok(config_str) => config_str,
+            Err(e) => {
+                tracing::error!("failed to read config file: {e}");
+                return;
+            }
+        };
+
+        match toml::from_str::<Config>(&config_str) {
+            Ok(config) => {
+                tracing::info!("config file changed on disk, reloading");
+                let overwritten = self.config.merge(&self.config_on_disk, config.clone());
+                if !overwritten.is_empty() {
+                    tracing::warn!(
+                        "unsaved settings overwritten by the config file: {overwritten:?}"
+                    );
+                }
+                self.overwritten_settings = overwritten;
+                self.config_on_disk = config;
+                credentials::load_passwords(&mut self.config.credentials);
+                self.config.register_secrets();
+                self.config_error = None;
+            }
+            Err(e) => {
+                tracing::error!("failed to parse config: {e:#?}");
+                self.config_error = Some(e.to_string());
+            }
+        }
+    }
+
+    fn log_download(&self, job: &DownloadJob) {
         let downloads_log_path = dirs::cache_dir()
             .expect("cache directory")
             .join("ytdlp-gui/downloads.log");
@@ -214,23 +688,52 @@ impl YtGUI {
             .open(downloads_log_path)
             .expect("downloads logs file");
 
-        // [<date-time>]::<URL>::<options>::<download-path>
+        // the time of each postprocessor, summed over the videos of a playlist
+        let mut steps: Vec<(&str, f64)> = Vec::new();
+        for step in &self.postprocessing_steps {
+            let seconds = step.duration.unwrap_or_default().as_secs_f64();
+
+            match steps
+                .iter_mut()
+                .find(|(name, _)| *name == step.postprocessor)
+            {
+                Some((_, total)) => *total += seconds,
+                None => steps.push((&step.postprocessor, seconds)),
+            }
+        }
+
+        let steps = steps
+            .iter()
+            .map(|(name, seconds)| format!("{name}={seconds:.2}s"))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let files = job
+            .files
+            .iter()
+            .map(|file| file.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" | ");
+
+        // [<date-time>]::<URL>::<options>::<download-path>::<postprocessing-steps>::<files>
         if let Err(e) = writeln!(
             file,
-            "{}::{}::{}::{}",
+            "{}::{}::{}::{}::{}::{}",
             Local::now(),
-            self.download_link,
-            match self.download_type {
+            job.links.join(" "),
+            match job.download_type {
                 DownloadType::Video => format!(
                     "{:?}:{:?}",
-                    self.config.options.video_resolution, self.config.options.video_format
+                    job.options.video_resolution, job.options.video_format
                 ),
                 DownloadType::Audio => format!(
                     "{:?}:{:?}",
-                    self.config.options.audio_quality, self.config.options.audio_format
+                    job.options.audio_quality, job.options.audio_format
                 ),
             },
-            self.config.download_folder.to_string_lossy()
+            job.download_folder.to_string_lossy(),
+            steps,
+            files,
         ) {
             tracing::error!("failed to log download: {e}");
         }
@@ -281,17 +784,17 @@ pub fn logging() {
         )
         .with(
             tracing_subscriber::fmt::Layer::default()
-                .with_writer(debug_file.with_max_level(Level::DEBUG))
+                .with_writer(Redacting(debug_file.with_max_level(Level::DEBUG)))
                 .with_ansi(false),
         )
         .with(
             tracing_subscriber::fmt::Layer::default()
-                .with_writer(warn_file.with_max_level(tracing::Level::WARN))
+                .with_writer(Redacting(warn_file.with_max_level(tracing::Level::WARN)))
                 .with_ansi(false),
         )
         .with(
             tracing_subscriber::fmt::Layer::default()
-                .with_writer(std::io::stdout.with_max_level(Level::DEBUG)),
+                .with_writer(Redacting(std::io::stdout.with_max_level(Level::DEBUG))),
         )
         .init();
 }
@@ -311,3 +814,43 @@ macro_rules! git_hash {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_tables() {
+        let table = |toml: &str| toml.parse::<toml::Table>().unwrap();
+
+        let on_disk = table(
+            "bin_path = 'yt-dlp'\nembed_metadata = false\nsave_window_position = false\nwindow_size = { width = 1.0, height = 1.0 }",
+        );
+        // unsaved changes made in the app
+        let current = table(
+            "bin_path = 'yt-dlp'\nembed_metadata = true\nsave_window_position = true\nwindow_size = { width = 2.0, height = 2.0 }",
+        );
+        // edits of the file made outside of the app
+        let external = table(
+            "bin_path = '/usr/bin/yt-dlp'\nembed_metadata = false\nsave_window_position = false\nwindow_size = { width = 3.0, height = 3.0 }",
+        );
+
+        let (merged, overwritten) = merge_tables(&on_disk, current, external);
+
+        assert_eq!(merged["bin_path"].as_str(), Some("/usr/bin/yt-dlp"));
+        // not edited in the file, the unsaved change is kept
+        assert_eq!(merged["embed_metadata"].as_bool(), Some(true));
+        assert_eq!(merged["window_size"]["width"].as_float(), Some(2.0));
+        assert!(overwritten.is_empty());
+
+        let on_disk = table("subscription_sync_interval = 60");
+        let current = table("subscription_sync_interval = 30");
+        let external = table("subscription_sync_interval = 120");
+        let (merged, overwritten) = merge_tables(&on_disk, current, external);
+        assert_eq!(merged["subscription_sync_interval"].as_integer(), Some(120));
+        assert_eq!(
+            overwritten,
+            vec![String::from("subscription_sync_interval")]
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Options for recording livestreams, empty values use yt-dlp's defaults
+#[derive(Deserialize, Serialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct LiveOptions {
+    /// Download the stream from its start instead of from now
+    pub live_from_start: bool,
+    /// Seconds between checks for a stream that hasn't started yet
+    pub wait_for_video: Option<u32>,
+    /// Minutes to record before stopping
+    pub max_duration: Option<u32>,
+}
+
+impl LiveOptions {
+    pub fn options(&self) -> Vec<String> {
+        // keeps the recording playable even if yt-dlp can't finish writing it
+        let mut options = vec![String::from("--hls-use-mpegts")];
+
+        if self.live_from_start {
+            options.push(String::from("--live-from-start"));
+        }
+
+        if let Some(wait_for_video) = self.wait_for_video {
+            options.extend([
+                String::from("--wait-for-video"),
+                wait_for_video.max(1).to_string(),
+            ]);
+        }
+
+        options
+    }
+
+    pub fn max_duration(&self) -> Option<Duration> {
+        self.max_duration
+            .map(|minutes| Duration::from_secs(u64::from(minutes) * 60))
+    }
+}
+
+/// State of the livestream that's being recorded
+#[derive(Debug, Default)]
+pub struct Recording {
+    /// When the stream started downloading, `None` while waiting for it to start
+    pub started: Option<Instant>,
+    pub recorded_bytes: Option<f32>,
+    pub speed: Option<f32>,
+    /// yt-dlp was asked to stop and is finishing the file
+    pub stopping: bool,
+}
+
+impl Recording {
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.started.map(|started| started.elapsed())
+    }
+
+    /// e.g. `REC 01:02:03 | 120.50MB | 1.20MB/s`
+    pub fn status(&self) -> Option<String> {
+        let elapsed = self.elapsed()?.as_secs();
+
+        let mut status = format!(
+            "REC {:02}:{:02}:{:02}",
+            elapsed / 3600,
+            elapsed % 3600 / 60,
+            elapsed % 60
+        );
+
+        if let Some(recorded_bytes) = self.recorded_bytes {
+            let recorded_megabytes = recorded_bytes / 1024_f32.powi(2);
+
+            if recorded_megabytes > 1024. {
+                status.push_str(&format!(" | {:.2}GB", recorded_megabytes / 1024.));
+            } else {
+                status.push_str(&format!(" | {recorded_megabytes:.2}MB"));
+            }
+        }
+
+        if let Some(speed) = self.speed {
+            status.push_str(&format!(" | {:.2}MB/s", speed / 1024_f32.powi(2)));
+        }
+
+        Some(status)
+    }
+}
@@ -5,7 +5,8 @@ use iced::{
     window::{self, Position},
 };
 use ytdlp_gui::{
-    Config, Flags, YtGUI, git_hash, logging, theme::ytdlp_gui_theme, update::check_for_update,
+    Config, Flags, SQUARE_THUMBNAIL_ARG, YtGUI, config_path, crop_to_square, git_hash, logging,
+    theme::ytdlp_gui_theme, update::check_for_update,
 };
 
 fn main() -> iced::Result {
@@ -30,6 +31,18 @@ fn main() -> iced::Result {
             std::process::exit(0);
         } else if arg == "--url" || arg == "-u" {
             url = std::env::args().nth(2);
+        } else if arg == SQUARE_THUMBNAIL_ARG {
+            // run by yt-dlp for square cover art, not meant to be used directly
+            let Some(path) = args.next() else {
+                std::process::exit(1);
+            };
+
+            if let Err(e) = crop_to_square(std::path::Path::new(&path)) {
+                eprintln!("failed to crop the thumbnail: {e}");
+                std::process::exit(1);
+            }
+
+            std::process::exit(0);
         } else {
             println!("Invalid option/argument");
             std::process::exit(1);
@@ -50,9 +63,12 @@ fn main() -> iced::Result {
 
     std::fs::create_dir_all(&config_dir).expect("create config dir");
 
-    let config = match std::fs::read_to_string(config_dir.join("config.toml")) {
+    let mut config_error = None;
+
+    let config = match std::fs::read_to_string(config_path()) {
         Ok(config_str) => toml::from_str::<Config>(&config_str).unwrap_or_else(|e| {
             tracing::error!("failed to parse config: {e:#?}");
+            config_error = Some(e.to_string());
             let config = Config::default();
             tracing::warn!("falling back to default configs: {config:#?}");
             config
@@ -79,7 +95,13 @@ fn main() -> iced::Result {
         Position::default()
     };
 
-    let flags = Flags { url, config };
+    let flags = Flags {
+        url,
+        config,
+        config_error,
+    };
+
+    let proxy = flags.config.proxy();
 
     let window_size = flags
         .config
@@ -95,7 +117,10 @@ fn main() -> iced::Result {
                 YtGUI::new(flags, sender),
                 iced::Task::batch([
                     iced::Task::stream(receiver),
-                    iced::Task::perform(check_for_update(), ytdlp_gui::Message::UpdateCheck),
+                    iced::Task::perform(
+                        check_for_update(proxy.clone()),
+                        ytdlp_gui::Message::UpdateCheck,
+                    ),
                 ]),
             )
         },
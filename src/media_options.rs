@@ -11,43 +11,254 @@ use crate::{
     theme::{pick_list_menu_style, pick_list_style},
 };
 
-#[derive(Deserialize, Serialize, Debug, Default, Copy, Clone)]
+#[derive(Deserialize, Serialize, Debug, Default, Copy, Clone, PartialEq)]
 pub struct Options {
     pub video_resolution: VideoResolution,
     pub video_format: VideoFormat,
     pub audio_quality: AudioQuality,
     pub audio_format: AudioFormat,
+    #[serde(default)]
+    pub video_codec: VideoCodec,
+    #[serde(default)]
+    pub audio_codec: AudioCodec,
+    #[serde(default)]
+    pub max_fps: MaxFps,
+    #[serde(default)]
+    pub dynamic_range: DynamicRange,
+    /// Fail when the chosen resolution isn't available instead of taking the closest one
+    #[serde(default)]
+    pub strict_resolution: bool,
+    /// Download the audio when the video has no video formats
+    #[serde(default)]
+    pub audio_only_fallback: bool,
+    #[serde(default)]
+    pub audio_bitrate: AudioBitrate,
+    #[serde(default)]
+    pub sample_rate: SampleRate,
+    #[serde(default)]
+    pub audio_channels: AudioChannels,
+    #[serde(default)]
+    pub loudness: Loudness,
+}
+
+/// EBU R128 loudness normalization of audio downloads with ffmpeg's `loudnorm` filter
+#[derive(Deserialize, Serialize, Debug, Copy, Clone, PartialEq)]
+#[serde(default)]
+pub struct Loudness {
+    pub normalize: bool,
+    /// Integrated loudness in LUFS
+    pub target: f32,
+    /// Maximum true peak in dBTP
+    pub true_peak: f32,
+}
+
+impl Default for Loudness {
+    fn default() -> Self {
+        // the usual target of podcasts
+        Self {
+            normalize: false,
+            target: -16.,
+            true_peak: -1.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum LoudnessError {
+    #[error("the target loudness has to be a number between -70 and -5 LUFS")]
+    InvalidTarget,
+    #[error("the true peak has to be a number between -9 and 0 dBTP")]
+    InvalidTruePeak,
+}
+
+impl Loudness {
+    pub fn parse_target(target: &str) -> Result<f32, LoudnessError> {
+        target
+            .trim()
+            .parse()
+            .ok()
+            .filter(|target| (-70. ..=-5.).contains(target))
+            .ok_or(LoudnessError::InvalidTarget)
+    }
+
+    pub fn parse_true_peak(true_peak: &str) -> Result<f32, LoudnessError> {
+        true_peak
+            .trim()
+            .parse()
+            .ok()
+            .filter(|true_peak| (-9. ..=0.).contains(true_peak))
+            .ok_or(LoudnessError::InvalidTruePeak)
+    }
+
+    pub fn filter(&self) -> String {
+        format!("loudnorm=I={}:TP={}:LRA=11", self.target, self.true_peak)
+    }
 }
 
 #[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum VideoResolution {
+    Best,
+    EightK,
     FourK,
     TwoK,
     #[default]
     FullHD,
     Hd,
     Sd,
+    P360,
+    P240,
+    P144,
+}
+
+impl VideoResolution {
+    pub const ALL: [VideoResolution; 10] = [
+        VideoResolution::Best,
+        VideoResolution::EightK,
+        VideoResolution::FourK,
+        VideoResolution::TwoK,
+        VideoResolution::FullHD,
+        VideoResolution::Hd,
+        VideoResolution::Sd,
+        VideoResolution::P360,
+        VideoResolution::P240,
+        VideoResolution::P144,
+    ];
+
+    /// `None` for the best available resolution
+    pub fn height(&self) -> Option<u32> {
+        match self {
+            VideoResolution::Best => None,
+            VideoResolution::EightK => Some(4320),
+            VideoResolution::FourK => Some(2160),
+            VideoResolution::TwoK => Some(1440),
+            VideoResolution::FullHD => Some(1080),
+            VideoResolution::Hd => Some(720),
+            VideoResolution::Sd => Some(480),
+            VideoResolution::P360 => Some(360),
+            VideoResolution::P240 => Some(240),
+            VideoResolution::P144 => Some(144),
+        }
+    }
 }
 
 impl core::fmt::Display for VideoResolution {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            VideoResolution::Best => f.write_str(&fl!("resolution_best")),
+            VideoResolution::EightK => write!(f, "8K"),
             VideoResolution::FourK => write!(f, "4K"),
             VideoResolution::TwoK => write!(f, "1440p"),
             VideoResolution::FullHD => write!(f, "1080p"),
             VideoResolution::Hd => write!(f, "720p"),
             VideoResolution::Sd => write!(f, "480p"),
+            VideoResolution::P360 => write!(f, "360p"),
+            VideoResolution::P240 => write!(f, "240p"),
+            VideoResolution::P144 => write!(f, "144p"),
+        }
+    }
+}
+
+/// Preferred over the site's preference, even when a higher resolution is
+/// available in another codec
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VideoCodec {
+    #[default]
+    Any,
+    Av1,
+    Vp9,
+    H264,
+    Hevc,
+}
+
+impl core::fmt::Display for VideoCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VideoCodec::Any => f.write_str(&fl!("codec_any")),
+            VideoCodec::Av1 => write!(f, "AV1"),
+            VideoCodec::Vp9 => write!(f, "VP9"),
+            VideoCodec::H264 => write!(f, "H.264"),
+            VideoCodec::Hevc => write!(f, "HEVC"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AudioCodec {
+    #[default]
+    Any,
+    Opus,
+    Aac,
+    Vorbis,
+    Mp3,
+}
+
+impl core::fmt::Display for AudioCodec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioCodec::Any => f.write_str(&fl!("codec_any")),
+            AudioCodec::Opus => write!(f, "Opus"),
+            AudioCodec::Aac => write!(f, "AAC"),
+            AudioCodec::Vorbis => write!(f, "Vorbis"),
+            AudioCodec::Mp3 => write!(f, "MP3"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MaxFps {
+    #[default]
+    Any,
+    Fps30,
+    Fps60,
+}
+
+impl core::fmt::Display for MaxFps {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaxFps::Any => f.write_str(&fl!("codec_any")),
+            MaxFps::Fps30 => write!(f, "30 fps"),
+            MaxFps::Fps60 => write!(f, "60 fps"),
         }
     }
 }
 
 #[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DynamicRange {
+    #[default]
+    Any,
+    Sdr,
+    Hdr,
+}
+
+impl core::fmt::Display for DynamicRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DynamicRange::Any => f.write_str(&fl!("codec_any")),
+            DynamicRange::Sdr => write!(f, "SDR"),
+            DynamicRange::Hdr => write!(f, "HDR"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+/// The container of video downloads, the downloaded streams are remuxed into it
+/// without re-encoding, except for the legacy containers that can't hold them
 pub enum VideoFormat {
+    /// Remuxed, VP9/AV1 and Opus streams are supported by ffmpeg's mp4 muxer
     #[default]
     Mp4,
+    /// Remuxed, holds any codec
     Mkv,
+    /// Remuxed, only VP8/VP9/AV1 video and Vorbis/Opus audio,
+    /// yt-dlp falls back to the original container for other codecs
     Webm,
-    // Flv,
+    /// Re-encoded to H.264/AAC, QuickTime container for editors which can't
+    /// hold the VP9/AV1 and Opus streams sites usually serve
+    Mov,
+    /// Re-encoded, sites don't serve streams that fit in AVI
+    Avi,
+    /// Re-encoded to H.264/AAC, the only codecs FLV supports
+    Flv,
 }
 
 impl core::fmt::Display for VideoFormat {
@@ -56,6 +267,9 @@ impl core::fmt::Display for VideoFormat {
             VideoFormat::Mp4 => write!(f, "MP4"),
             VideoFormat::Mkv => write!(f, "MKV"),
             VideoFormat::Webm => write!(f, "WEBM"),
+            VideoFormat::Mov => write!(f, "MOV"),
+            VideoFormat::Avi => write!(f, "AVI"),
+            VideoFormat::Flv => write!(f, "FLV"),
         }
     }
 }
@@ -80,15 +294,85 @@ impl core::fmt::Display for AudioQuality {
     }
 }
 
+/// A constant bitrate for lossy formats, instead of the VBR `AudioQuality`
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AudioBitrate {
+    #[default]
+    Vbr,
+    K128,
+    K192,
+    K256,
+    K320,
+}
+
+impl core::fmt::Display for AudioBitrate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioBitrate::Vbr => f.write_str(&fl!("bitrate_variable")),
+            AudioBitrate::K128 => write!(f, "128 kbps"),
+            AudioBitrate::K192 => write!(f, "192 kbps"),
+            AudioBitrate::K256 => write!(f, "256 kbps"),
+            AudioBitrate::K320 => write!(f, "320 kbps"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SampleRate {
+    #[default]
+    Original,
+    Hz44100,
+    Hz48000,
+}
+
+impl core::fmt::Display for SampleRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SampleRate::Original => f.write_str(&fl!("audio_original")),
+            SampleRate::Hz44100 => write!(f, "44.1 kHz"),
+            SampleRate::Hz48000 => write!(f, "48 kHz"),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AudioChannels {
+    #[default]
+    Original,
+    Mono,
+    Stereo,
+}
+
+impl core::fmt::Display for AudioChannels {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioChannels::Original => f.write_str(&fl!("audio_original")),
+            AudioChannels::Mono => f.write_str(&fl!("audio_mono")),
+            AudioChannels::Stereo => f.write_str(&fl!("audio_stereo")),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+/// The format audio is extracted to with `-x`, every format except `Best` is
+/// converted with ffmpeg, unless the downloaded audio already has that codec
 pub enum AudioFormat {
     #[default]
     Mp3,
+    /// Lossless, uncompressed
     Wav,
     Vorbis,
+    /// AAC in an MP4 container
     M4a,
     Opus,
+    /// Lossless
     Flac,
+    /// Raw AAC stream, the same audio as M4A without a container
+    Aac,
+    /// Lossless, in an M4A container
+    Alac,
+    /// The downloaded audio as is, without re-encoding
+    Best,
 }
 
 impl core::fmt::Display for AudioFormat {
@@ -100,18 +384,63 @@ impl core::fmt::Display for AudioFormat {
             AudioFormat::M4a => write!(f, "M4A"),
             AudioFormat::Opus => write!(f, "OPUS"),
             AudioFormat::Flac => write!(f, "FLAC"),
+            AudioFormat::Aac => write!(f, "AAC"),
+            AudioFormat::Alac => write!(f, "ALAC"),
+            AudioFormat::Best => f.write_str(&fl!("audio_format_best")),
         }
     }
 }
 
 impl VideoResolution {
-    pub fn options(&self) -> &str {
+    pub fn options(&self) -> String {
+        match self.height() {
+            Some(height) => format!("res:{height}"),
+            None => String::from("res"),
+        }
+    }
+}
+
+impl VideoCodec {
+    pub fn options(&self) -> Option<&str> {
+        match self {
+            VideoCodec::Any => None,
+            VideoCodec::Av1 => Some("vcodec:av01"),
+            VideoCodec::Vp9 => Some("vcodec:vp9"),
+            VideoCodec::H264 => Some("vcodec:h264"),
+            VideoCodec::Hevc => Some("vcodec:h265"),
+        }
+    }
+}
+
+impl AudioCodec {
+    pub fn options(&self) -> Option<&str> {
+        match self {
+            AudioCodec::Any => None,
+            AudioCodec::Opus => Some("acodec:opus"),
+            AudioCodec::Aac => Some("acodec:aac"),
+            AudioCodec::Vorbis => Some("acodec:vorbis"),
+            AudioCodec::Mp3 => Some("acodec:mp3"),
+        }
+    }
+}
+
+impl MaxFps {
+    pub fn options(&self) -> Option<&str> {
         match self {
-            VideoResolution::FourK => "res:2160",
-            VideoResolution::TwoK => "res:1440",
-            VideoResolution::FullHD => "res:1080",
-            VideoResolution::Hd => "res:720",
-            VideoResolution::Sd => "res:480",
+            MaxFps::Any => None,
+            MaxFps::Fps30 => Some("fps:30"),
+            MaxFps::Fps60 => Some("fps:60"),
+        }
+    }
+}
+
+impl DynamicRange {
+    pub fn options(&self) -> Option<&str> {
+        match self {
+            DynamicRange::Any => None,
+            // the limit is the best allowed range, the default order prefers the best one
+            DynamicRange::Sdr => Some("hdr:sdr"),
+            DynamicRange::Hdr => Some("hdr"),
         }
     }
 }
@@ -122,7 +451,36 @@ impl VideoFormat {
             VideoFormat::Mp4 => "mp4",
             VideoFormat::Mkv => "mkv",
             VideoFormat::Webm => "webm",
+            VideoFormat::Mov => "mov",
+            VideoFormat::Avi => "avi",
+            VideoFormat::Flv => "flv",
+        }
+    }
+
+    /// The containers that can't hold the streams sites serve are re-encoded,
+    /// which is a lot slower than remuxing
+    pub fn needs_recode(&self) -> bool {
+        matches!(self, VideoFormat::Mov | VideoFormat::Avi | VideoFormat::Flv)
+    }
+
+    /// `--remux-video` or `--recode-video` with the format
+    pub fn conversion_options(&self) -> Vec<String> {
+        let conversion = if self.needs_recode() {
+            "--recode-video"
+        } else {
+            "--remux-video"
+        };
+
+        let mut options = vec![conversion.to_string(), self.options().to_string()];
+
+        // yt-dlp only passes the codecs for AVI, ffmpeg's defaults
+        // depend on the build and are FLV1/MP3 for FLV
+        if matches!(self, VideoFormat::Mov | VideoFormat::Flv) {
+            options.push(String::from("--ppa"));
+            options.push(String::from("VideoConvertor:-c:v libx264 -c:a aac"));
         }
+
+        options
     }
 }
 
@@ -135,6 +493,75 @@ impl AudioFormat {
             AudioFormat::Opus => "opus",
             AudioFormat::M4a => "m4a",
             AudioFormat::Flac => "flac",
+            AudioFormat::Aac => "aac",
+            AudioFormat::Alac => "alac",
+            AudioFormat::Best => "best",
+        }
+    }
+
+    /// Lossless formats don't have a bitrate or a quality
+    pub fn is_lossless(&self) -> bool {
+        matches!(
+            self,
+            AudioFormat::Wav | AudioFormat::Flac | AudioFormat::Alac
+        )
+    }
+
+    /// The ffmpeg encoder of the format, passed explicitly when filtering the audio since
+    /// yt-dlp copies the stream when it already has the chosen codec, and ffmpeg can't
+    /// filter a copied stream
+    pub fn encoder(&self) -> Option<&str> {
+        match self {
+            AudioFormat::Mp3 => Some("libmp3lame"),
+            AudioFormat::Wav => Some("pcm_s16le"),
+            AudioFormat::Vorbis => Some("libvorbis"),
+            AudioFormat::M4a | AudioFormat::Aac => Some("aac"),
+            AudioFormat::Opus => Some("libopus"),
+            AudioFormat::Flac => Some("flac"),
+            AudioFormat::Alac => Some("alac"),
+            AudioFormat::Best => None,
+        }
+    }
+
+    /// The audio isn't re-encoded for `Best`, so none of the encoding options apply
+    pub fn reencodes(&self) -> bool {
+        *self != AudioFormat::Best
+    }
+
+    /// Opus only supports 48 kHz
+    pub fn supports_sample_rate(&self) -> bool {
+        *self != AudioFormat::Opus
+    }
+}
+
+impl AudioBitrate {
+    pub fn options(&self) -> Option<&str> {
+        match self {
+            AudioBitrate::Vbr => None,
+            AudioBitrate::K128 => Some("128K"),
+            AudioBitrate::K192 => Some("192K"),
+            AudioBitrate::K256 => Some("256K"),
+            AudioBitrate::K320 => Some("320K"),
+        }
+    }
+}
+
+impl SampleRate {
+    pub fn options(&self) -> Option<&str> {
+        match self {
+            SampleRate::Original => None,
+            SampleRate::Hz44100 => Some("44100"),
+            SampleRate::Hz48000 => Some("48000"),
+        }
+    }
+}
+
+impl AudioChannels {
+    pub fn options(&self) -> Option<&str> {
+        match self {
+            AudioChannels::Original => None,
+            AudioChannels::Mono => Some("1"),
+            AudioChannels::Stereo => Some("2"),
         }
     }
 }
@@ -151,19 +578,200 @@ impl AudioQuality {
 }
 
 impl Options {
+    /// The `-S` sort of video downloads, earlier fields take precedence so the codecs
+    /// are preferred over the resolution
+    pub fn format_sort(&self) -> String {
+        [
+            self.video_codec.options(),
+            self.audio_codec.options(),
+            self.dynamic_range.options(),
+            self.max_fps.options(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(ToString::to_string)
+        .chain([self.video_resolution.options()])
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+
+    /// The extraction arguments of audio downloads, options that don't apply
+    /// to the chosen format are left out
+    pub fn audio_options(&self) -> Vec<String> {
+        let format = self.audio_format;
+
+        let mut options = vec![
+            String::from("-x"),
+            String::from("--audio-format"),
+            format.options().to_string(),
+        ];
+
+        if !format.reencodes() {
+            return options;
+        }
+
+        if !format.is_lossless() {
+            let quality = self
+                .audio_bitrate
+                .options()
+                .unwrap_or(self.audio_quality.options());
+
+            options.extend([String::from("--audio-quality"), quality.to_string()]);
+        }
+
+        let mut ffmpeg_args = Vec::new();
+
+        if let Some(sample_rate) = self.sample_rate.options()
+            && format.supports_sample_rate()
+        {
+            ffmpeg_args.push(format!("-ar {sample_rate}"));
+        }
+
+        if let Some(channels) = self.audio_channels.options() {
+            ffmpeg_args.push(format!("-ac {channels}"));
+        }
+
+        if self.loudness.normalize {
+            ffmpeg_args.push(format!("-af {}", self.loudness.filter()));
+        }
+
+        if let Some(encoder) = format.encoder()
+            && !ffmpeg_args.is_empty()
+        {
+            ffmpeg_args.push(format!("-c:a {encoder}"));
+        }
+
+        if !ffmpeg_args.is_empty() {
+            options.extend([
+                String::from("--ppa"),
+                format!("ExtractAudio+ffmpeg_o:{}", ffmpeg_args.join(" ")),
+            ]);
+        }
+
+        options
+    }
+
+    pub fn audio_encoding(options: Options) -> iced::widget::Row<'static, Message> {
+        let format = options.audio_format;
+        let mut preferences = Vec::new();
+
+        if !format.reencodes() {
+            return dir_row(preferences);
+        }
+
+        if !format.is_lossless() {
+            preferences.push(preference(
+                fl!("bitrate"),
+                &[
+                    AudioBitrate::Vbr,
+                    AudioBitrate::K128,
+                    AudioBitrate::K192,
+                    AudioBitrate::K256,
+                    AudioBitrate::K320,
+                ],
+                options.audio_bitrate,
+                Message::SelectedAudioBitrate,
+            ));
+        }
+
+        if format.supports_sample_rate() {
+            preferences.push(preference(
+                fl!("sample_rate"),
+                &[
+                    SampleRate::Original,
+                    SampleRate::Hz44100,
+                    SampleRate::Hz48000,
+                ],
+                options.sample_rate,
+                Message::SelectedSampleRate,
+            ));
+        }
+
+        preferences.push(preference(
+            fl!("channels"),
+            &[
+                AudioChannels::Original,
+                AudioChannels::Mono,
+                AudioChannels::Stereo,
+            ],
+            options.audio_channels,
+            Message::SelectedAudioChannels,
+        ));
+
+        dir_row(preferences)
+            .spacing(SPACING * 2.)
+            .width(iced::Length::Shrink)
+            .align_y(iced::Alignment::Center)
+    }
+
+    /// The `-f` of video downloads, `None` leaves it to yt-dlp's default `bv*+ba/b`
+    pub fn format_selector(&self) -> Option<String> {
+        let selector = match self.video_resolution.height() {
+            Some(height) if self.strict_resolution => {
+                format!("bv*[height={height}]+ba/b[height={height}]")
+            }
+            _ if self.audio_only_fallback => String::from("bv*+ba/b"),
+            _ => return None,
+        };
+
+        Some(if self.audio_only_fallback {
+            format!("{selector}/ba")
+        } else {
+            selector
+        })
+    }
+
+    pub fn codec_preferences(options: Options) -> iced::widget::Row<'static, Message> {
+        dir_row(vec![
+            preference(
+                fl!("video_codec"),
+                &[
+                    VideoCodec::Any,
+                    VideoCodec::Av1,
+                    VideoCodec::Vp9,
+                    VideoCodec::H264,
+                    VideoCodec::Hevc,
+                ],
+                options.video_codec,
+                Message::SelectedVideoCodec,
+            ),
+            preference(
+                fl!("audio_codec"),
+                &[
+                    AudioCodec::Any,
+                    AudioCodec::Opus,
+                    AudioCodec::Aac,
+                    AudioCodec::Vorbis,
+                    AudioCodec::Mp3,
+                ],
+                options.audio_codec,
+                Message::SelectedAudioCodec,
+            ),
+            preference(
+                fl!("max_fps"),
+                &[MaxFps::Any, MaxFps::Fps30, MaxFps::Fps60],
+                options.max_fps,
+                Message::SelectedMaxFps,
+            ),
+            preference(
+                fl!("dynamic_range"),
+                &[DynamicRange::Any, DynamicRange::Sdr, DynamicRange::Hdr],
+                options.dynamic_range,
+                Message::SelectedDynamicRange,
+            ),
+        ])
+        .spacing(SPACING * 2.)
+        .width(iced::Length::Shrink)
+        .align_y(iced::Alignment::Center)
+    }
+
     pub fn video_resolutions(resolution: VideoResolution) -> iced::widget::Row<'static, Message> {
         dir_row(vec![
             text(format!("{}: ", fl!("resolution")))
                 .size(FONT_SIZE)
                 .into(),
             pick_list(
-                vec![
-                    VideoResolution::FourK,
-                    VideoResolution::TwoK,
-                    VideoResolution::FullHD,
-                    VideoResolution::Hd,
-                    VideoResolution::Sd,
-                ],
+                VideoResolution::ALL,
                 Some(resolution),
                 Message::SelectedResolution,
             )
@@ -183,7 +791,14 @@ impl Options {
                 .size(FONT_SIZE)
                 .into(),
             pick_list(
-                vec![VideoFormat::Mp4, VideoFormat::Mkv, VideoFormat::Webm],
+                vec![
+                    VideoFormat::Mp4,
+                    VideoFormat::Mkv,
+                    VideoFormat::Webm,
+                    VideoFormat::Mov,
+                    VideoFormat::Avi,
+                    VideoFormat::Flv,
+                ],
                 Some(format),
                 Message::SelectedVideoFormat,
             )
@@ -210,6 +825,9 @@ impl Options {
                     AudioFormat::M4a,
                     AudioFormat::Opus,
                     AudioFormat::Flac,
+                    AudioFormat::Aac,
+                    AudioFormat::Alac,
+                    AudioFormat::Best,
                 ],
                 Some(format),
                 Message::SelectedAudioFormat,
@@ -248,12 +866,34 @@ impl Options {
     }
 }
 
+/// A labeled pick list
+fn preference<T: ToString + PartialEq + Clone + 'static>(
+    label: String,
+    values: &[T],
+    selected: T,
+    on_select: fn(T) -> Message,
+) -> iced::Element<'static, Message> {
+    dir_row(vec![
+        text(format!("{label}: ")).size(FONT_SIZE).into(),
+        pick_list(values.to_vec(), Some(selected), on_select)
+            .text_size(FONT_SIZE)
+            .menu_style(pick_list_menu_style)
+            .style(pick_list_style)
+            .into(),
+    ])
+    .spacing(SPACING)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
 pub fn playlist_options(is_playlist: bool, download_folder: PathBuf) -> Vec<String> {
     let download_dir = download_folder.to_string_lossy().to_string();
 
     if is_playlist {
         vec![
             String::from("--yes-playlist"),
+            // an unavailable item shouldn't stop the rest of the playlist
+            String::from("--ignore-errors"),
             String::from("-P"),
             download_dir,
             String::from("-o"),
@@ -272,3 +912,120 @@ pub fn playlist_options(is_playlist: bool, download_folder: PathBuf) -> Vec<Stri
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_sort() {
+        assert_eq!(Options::default().format_sort(), "res:1080");
+
+        let options = Options {
+            video_codec: VideoCodec::H264,
+            dynamic_range: DynamicRange::Sdr,
+            max_fps: MaxFps::Fps30,
+            ..Default::default()
+        };
+        assert_eq!(options.format_sort(), "vcodec:h264,hdr:sdr,fps:30,res:1080");
+    }
+
+    #[test]
+    fn test_format_selector() {
+        assert_eq!(Options::default().format_selector(), None);
+
+        let mut options = Options {
+            video_resolution: VideoResolution::Hd,
+            strict_resolution: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            options.format_selector().as_deref(),
+            Some("bv*[height=720]+ba/b[height=720]")
+        );
+
+        options.audio_only_fallback = true;
+        assert_eq!(
+            options.format_selector().as_deref(),
+            Some("bv*[height=720]+ba/b[height=720]/ba")
+        );
+
+        // there's no exact resolution to enforce
+        options.video_resolution = VideoResolution::Best;
+        assert_eq!(options.format_selector().as_deref(), Some("bv*+ba/b/ba"));
+    }
+
+    #[test]
+    fn test_conversion_options() {
+        assert_eq!(
+            VideoFormat::Mkv.conversion_options(),
+            vec![String::from("--remux-video"), String::from("mkv")]
+        );
+        assert_eq!(
+            VideoFormat::Avi.conversion_options(),
+            vec![String::from("--recode-video"), String::from("avi")]
+        );
+        assert_eq!(
+            VideoFormat::Flv.conversion_options(),
+            vec![
+                String::from("--recode-video"),
+                String::from("flv"),
+                String::from("--ppa"),
+                String::from("VideoConvertor:-c:v libx264 -c:a aac"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_audio_options() {
+        let mut options = Options {
+            audio_format: AudioFormat::Opus,
+            audio_bitrate: AudioBitrate::K192,
+            sample_rate: SampleRate::Hz44100,
+            audio_channels: AudioChannels::Mono,
+            ..Default::default()
+        };
+
+        // opus is always 48 kHz
+        assert_eq!(
+            options.audio_options(),
+            vec![
+                "-x",
+                "--audio-format",
+                "opus",
+                "--audio-quality",
+                "192K",
+                "--ppa",
+                "ExtractAudio+ffmpeg_o:-ac 1 -c:a libopus"
+            ]
+        );
+
+        // lossless formats don't have a quality
+        options.audio_format = AudioFormat::Flac;
+        assert_eq!(
+            options.audio_options(),
+            vec![
+                "-x",
+                "--audio-format",
+                "flac",
+                "--ppa",
+                "ExtractAudio+ffmpeg_o:-ar 44100 -ac 1 -c:a flac"
+            ]
+        );
+
+        options.loudness.normalize = true;
+        options.audio_channels = AudioChannels::Original;
+        options.sample_rate = SampleRate::Original;
+        assert_eq!(
+            options.audio_options().last().map(String::as_str),
+            Some("ExtractAudio+ffmpeg_o:-af loudnorm=I=-16:TP=-1.5:LRA=11 -c:a flac")
+        );
+
+        // the audio is copied as is
+        options.audio_format = AudioFormat::Best;
+        assert_eq!(
+            options.audio_options(),
+            vec!["-x", "--audio-format", "best"]
+        );
+    }
+}
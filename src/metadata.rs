@@ -0,0 +1,202 @@
+//! Tags written to the downloaded files with `--embed-metadata`.
+//!
+//! The tags are passed as extra `-metadata` arguments to the ffmpeg run of yt-dlp's
+//! metadata postprocessor, they come after yt-dlp's own values so they take precedence.
+
+use serde::{Deserialize, Serialize};
+
+use crate::probe::MediaInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagField {
+    Artist,
+    Album,
+    Title,
+    Track,
+    Year,
+    Genre,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Tags {
+    pub artist: String,
+    pub album: String,
+    pub title: String,
+    /// `N` or `N/TOTAL`
+    pub track: String,
+    pub year: String,
+    pub genre: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TagError {
+    #[error("invalid track \"{0}\", use a number like 3 or 3/12")]
+    InvalidTrack(String),
+    #[error("invalid year \"{0}\"")]
+    InvalidYear(String),
+}
+
+impl Tags {
+    /// Fills the tags from the fetched info of a video, music videos usually have
+    /// the artist/album/track fields, otherwise the uploader and title are used
+    pub fn from_info(info: &MediaInfo) -> Self {
+        let year = info.release_year.map(|year| year.to_string()).or_else(|| {
+            info.upload_date
+                .as_ref()
+                .and_then(|date| date.get(..4))
+                .map(ToString::to_string)
+        });
+
+        Tags {
+            artist: info
+                .artist
+                .clone()
+                .or_else(|| info.uploader.clone())
+                .unwrap_or_default(),
+            album: info.album.clone().unwrap_or_default(),
+            title: info
+                .track
+                .clone()
+                .or_else(|| info.title.clone())
+                .unwrap_or_default(),
+            track: info
+                .track_number
+                .map(|track| track.to_string())
+                .unwrap_or_default(),
+            year: year.unwrap_or_default(),
+            genre: info.genre.clone().unwrap_or_default(),
+        }
+    }
+
+    pub fn get(&self, field: TagField) -> &str {
+        match field {
+            TagField::Artist => &self.artist,
+            TagField::Album => &self.album,
+            TagField::Title => &self.title,
+            TagField::Track => &self.track,
+            TagField::Year => &self.year,
+            TagField::Genre => &self.genre,
+        }
+    }
+
+    pub fn set(&mut self, field: TagField, value: String) {
+        match field {
+            TagField::Artist => self.artist = value,
+            TagField::Album => self.album = value,
+            TagField::Title => self.title = value,
+            TagField::Track => self.track = value,
+            TagField::Year => self.year = value,
+            TagField::Genre => self.genre = value,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), TagError> {
+        let track = self.track.trim();
+        let is_number = |n: &str| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit());
+
+        if !track.is_empty() {
+            let valid = match track.split_once('/') {
+                Some((number, total)) => is_number(number) && is_number(total),
+                None => is_number(track),
+            };
+
+            if !valid {
+                return Err(TagError::InvalidTrack(track.to_string()));
+            }
+        }
+
+        let year = self.year.trim();
+        if !year.is_empty() && (year.len() != 4 || !is_number(year)) {
+            return Err(TagError::InvalidYear(year.to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        let mut options = vec![String::from("--embed-metadata")];
+
+        // ffmpeg's metadata keys
+        let args: Vec<String> = [
+            ("artist", &self.artist),
+            ("album", &self.album),
+            ("title", &self.title),
+            ("track", &self.track),
+            ("date", &self.year),
+            ("genre", &self.genre),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(key, value)| format!("-metadata {}", quote(&format!("{key}={}", value.trim()))))
+        .collect();
+
+        if !args.is_empty() {
+            options.extend([
+                String::from("--ppa"),
+                format!("Metadata+ffmpeg_o:{}", args.join(" ")),
+            ]);
+        }
+
+        options
+    }
+}
+
+/// Quotes `value` for yt-dlp's `--ppa` which splits the arguments like a shell
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r#"'"'"'"#))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tags_options() {
+        let tags = Tags {
+            artist: String::from("Queen"),
+            album: String::from("A Night at the Opera"),
+            title: String::from("'39"),
+            track: String::from("5/12"),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            tags.options(),
+            vec![
+                String::from("--embed-metadata"),
+                String::from("--ppa"),
+                String::from(
+                    r#"Metadata+ffmpeg_o:-metadata 'artist=Queen' -metadata 'album=A Night at the Opera' -metadata 'title='"'"'39' -metadata 'track=5/12'"#
+                ),
+            ]
+        );
+        assert_eq!(
+            Tags::default().options(),
+            vec![String::from("--embed-metadata")]
+        );
+    }
+
+    #[test]
+    fn test_validate_tags() {
+        let mut tags = Tags {
+            track: String::from("3/12"),
+            year: String::from("1975"),
+            ..Default::default()
+        };
+        assert_eq!(tags.validate(), Ok(()));
+
+        tags.track = String::from("three");
+        assert_eq!(
+            tags.validate(),
+            Err(TagError::InvalidTrack(String::from("three")))
+        );
+
+        tags.track.clear();
+        tags.year = String::from("75");
+        assert_eq!(
+            tags.validate(),
+            Err(TagError::InvalidYear(String::from("75")))
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Desktop notifications for finished and failed downloads.
+//!
+//! On Linux and the BSDs the notifications have actions ("Open file", "Open folder", "Retry"),
+//! clicking one of them sends the matching message to the app.
+
+use std::path::{Path, PathBuf};
+
+use iced::futures::channel::mpsc::UnboundedSender;
+use notify_rust::Notification;
+use serde::{Deserialize, Serialize};
+
+use crate::{Message, download::DownloadJob, fl};
+
+/// Which events show a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub download_finished: bool,
+    pub download_failed: bool,
+    pub hook_failed: bool,
+    pub subscription_new_items: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            download_finished: true,
+            download_failed: true,
+            hook_failed: true,
+            subscription_new_items: true,
+        }
+    }
+}
+
+/// A video of the running download, used for the notification once the download finishes
+#[derive(Debug, Clone, Default)]
+pub struct FinishedVideo {
+    pub title: String,
+    /// The URL of the video's thumbnail
+    pub thumbnail: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+enum Action {
+    OpenFile(PathBuf),
+    OpenFolder(PathBuf),
+    Retry(Box<DownloadJob>),
+}
+
+impl Action {
+    fn id(&self) -> &'static str {
+        match self {
+            Action::OpenFile(_) => "open_file",
+            Action::OpenFolder(_) => "open_folder",
+            Action::Retry(_) => "retry",
+        }
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Action::OpenFile(_) => fl!("open_file"),
+            Action::OpenFolder(_) => fl!("open_folder"),
+            Action::Retry(_) => fl!("retry"),
+        }
+    }
+
+    fn message(self) -> Message {
+        match self {
+            Action::OpenFile(path) => Message::OpenFile(path),
+            Action::OpenFolder(path) => Message::ShowInFolder(path),
+            Action::Retry(job) => Message::RetryDownload(job),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadNotification {
+    summary: String,
+    body: String,
+    thumbnail: Option<String>,
+    actions: Vec<Action>,
+}
+
+impl DownloadNotification {
+    /// A single video shows its title and size, playlists and multiple videos are
+    /// grouped into one summary with the number of files and their total size
+    pub fn finished(message: &str, job: &DownloadJob, videos: &[FinishedVideo]) -> Self {
+        let size = format_size(job.files.iter().map(|file| file_size(file)).sum());
+
+        let (body, actions) = match (videos, job.files.as_slice()) {
+            ([video], [file]) => (
+                format!("{}\n{size}", video.title),
+                vec![
+                    Action::OpenFile(file.clone()),
+                    Action::OpenFolder(file.clone()),
+                ],
+            ),
+            (_, files) => (
+                fl!("notification_files", count = files.len(), size = size),
+                files
+                    .first()
+                    .map(|file| Action::OpenFolder(file.clone()))
+                    .into_iter()
+                    .collect(),
+            ),
+        };
+
+        Self {
+            summary: message.to_string(),
+            body,
+            thumbnail: videos.iter().find_map(|video| video.thumbnail.clone()),
+            actions,
+        }
+    }
+
+    pub fn failed(error: &str, job: &DownloadJob) -> Self {
+        let mut job = job.clone();
+        job.files.clear();
+        job.netrc = None;
+
+        Self {
+            summary: fl!("download_failed"),
+            body: error.to_string(),
+            thumbnail: None,
+            actions: vec![Action::Retry(Box::new(job))],
+        }
+    }
+
+    /// Shows the notification in the background, the thumbnail is fetched through `proxy` if it's set
+    pub fn show(self, proxy: Option<String>, sender: UnboundedSender<Message>) {
+        std::thread::spawn(move || {
+            let mut notification = Notification::new();
+            notification.summary(&self.summary).body(&self.body);
+
+            // kept until the notification is closed, the file is removed when it's dropped
+            #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+            let thumbnail = self
+                .thumbnail
+                .as_deref()
+                .and_then(|url| fetch_thumbnail(url, proxy.as_deref()));
+
+            #[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+            if let Some(thumbnail) = &thumbnail {
+                notification.image_path(&thumbnail.path().to_string_lossy());
+            }
+
+            #[cfg(not(any(target_os = "windows", all(unix, not(target_os = "macos")))))]
+            let _ = proxy;
+
+            #[cfg(all(unix, not(target_os = "macos")))]
+            for action in &self.actions {
+                notification.action(action.id(), &action.label());
+            }
+
+            let handle = match notification.show() {
+                Ok(handle) => handle,
+                Err(e) => {
+                    tracing::error!("failed to show notification: {e}");
+                    return;
+                }
+            };
+
+            #[cfg(all(unix, not(target_os = "macos")))]
+            handle.wait_for_action(|id| {
+                if let Some(action) = self.actions.into_iter().find(|action| action.id() == id) {
+                    let _ = sender.unbounded_send(action.message());
+                }
+            });
+
+            #[cfg(not(all(unix, not(target_os = "macos"))))]
+            let _ = (handle, sender);
+        });
+    }
+}
+
+/// Shows a notification without a body or actions
+pub fn notify(summary: &str) {
+    if let Err(e) = Notification::new().summary(summary).show() {
+        tracing::error!("failed to show notification: {e}");
+    }
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .map(|metadata| metadata.len())
+        .unwrap_or_default()
+}
+
+fn format_size(bytes: u64) -> String {
+    let megabytes = bytes as f64 / 1024_f64.powi(2);
+
+    if megabytes > 1024. {
+        format!("{:.2}GB", megabytes / 1024.)
+    } else {
+        format!("{:.2}MB", megabytes)
+    }
+}
+
+/// Downloads the thumbnail into a temporary file for the notification's image,
+/// every notification has its own file so they don't show each other's thumbnails
+#[cfg(any(target_os = "windows", all(unix, not(target_os = "macos"))))]
+fn fetch_thumbnail(url: &str, proxy: Option<&str>) -> Option<tempfile::NamedTempFile> {
+    let result = (|| -> Result<tempfile::NamedTempFile, Box<dyn std::error::Error>> {
+        let mut client =
+            reqwest::blocking::Client::builder().timeout(std::time::Duration::from_secs(10));
+
+        if let Some(proxy) = proxy {
+            client = client.proxy(reqwest::Proxy::all(proxy)?);
+        }
+
+        let bytes = client
+            .build()?
+            .get(url)
+            .send()?
+            .error_for_status()?
+            .bytes()?;
+
+        let extension = url::Url::parse(url)
+            .ok()
+            .and_then(|url| {
+                Path::new(url.path())
+                    .extension()
+                    .map(|extension| extension.to_string_lossy().to_string())
+            })
+            .unwrap_or_else(|| String::from("jpg"));
+
+        let mut file = tempfile::Builder::new()
+            .prefix("ytdlp-gui-thumbnail-")
+            .suffix(&format!(".{extension}"))
+            .tempfile()?;
+        std::io::Write::write_all(&mut file, &bytes)?;
+
+        Ok(file)
+    })();
+
+    result
+        .inspect_err(|e| tracing::error!("failed to fetch the notification thumbnail: {e}"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0.00MB");
+        assert_eq!(format_size(52_428_800), "50.00MB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.00GB");
+    }
+}
@@ -0,0 +1,205 @@
+//! The items of a playlist download that were skipped or failed.
+//!
+//! Playlists are downloaded with `--ignore-errors` so one broken item doesn't stop
+//! the rest, the errors are collected here and shown once the playlist finishes.
+
+use iced::widget::{column, text};
+
+use crate::{
+    Message, YtGUI, app::SPACING, download::DownloadJob, fl, i18n::dir_row,
+    progress::ProgressError, theme::button,
+};
+
+#[derive(Debug, Clone)]
+pub struct FailedItem {
+    /// The id of the playlist entry, used to retry it
+    pub id: Option<String>,
+    pub reason: String,
+}
+
+impl core::fmt::Display for FailedItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.id {
+            Some(id) => write!(f, "{id}: {}", self.reason),
+            None => f.write_str(&self.reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlaylistReport {
+    job: DownloadJob,
+    pub succeeded: usize,
+    /// Entries that can't be downloaded, like private and unavailable videos
+    pub skipped: Vec<FailedItem>,
+    pub failed: Vec<FailedItem>,
+    /// The entry being downloaded, for errors that don't mention it
+    current_id: Option<String>,
+}
+
+impl PlaylistReport {
+    pub fn new(job: DownloadJob) -> Self {
+        Self {
+            job,
+            succeeded: 0,
+            skipped: Vec::new(),
+            failed: Vec::new(),
+            current_id: None,
+        }
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.skipped.is_empty() || !self.failed.is_empty()
+    }
+
+    pub fn started_item(&mut self, id: String) {
+        self.current_id = Some(id);
+    }
+
+    pub fn finished_item(&mut self) {
+        self.succeeded += 1;
+        self.current_id = None;
+    }
+
+    /// Records the error of an entry, returns `false` for errors that end the whole playlist
+    pub fn record_error(&mut self, error: &ProgressError, line: &str) -> bool {
+        let id = line
+            .strip_prefix("stderr:ERROR: ")
+            .and_then(item_id)
+            .map(String::from)
+            .or_else(|| self.current_id.take());
+
+        let item = FailedItem {
+            id,
+            reason: error.to_string(),
+        };
+
+        match error {
+            // yt-dlp continues with the existing file
+            ProgressError::AlreadyExists => {}
+            ProgressError::PrivateVideo | ProgressError::VideoUnavailable => {
+                self.skipped.push(item)
+            }
+            ProgressError::YtDlp { category, .. } if category.is_unavailable() => {
+                self.skipped.push(item)
+            }
+            ProgressError::YtDlp { .. } | ProgressError::Other(_) => self.failed.push(item),
+            ProgressError::PlaylistNotChecked | ProgressError::NoPlaylist => return false,
+        }
+
+        true
+    }
+
+    pub fn summary(&self) -> String {
+        fl!(
+            "playlist_report",
+            succeeded = self.succeeded,
+            skipped = self.skipped.len(),
+            failed = self.failed.len()
+        )
+    }
+
+    /// The playlist job limited to the failed entries, `None` when none of them can be found again
+    pub fn retry_job(&self) -> Option<DownloadJob> {
+        let ids: Vec<String> = self
+            .failed
+            .iter()
+            .filter_map(|item| item.id.clone())
+            .collect();
+
+        if ids.is_empty() {
+            return None;
+        }
+
+        let mut job = self.job.clone();
+        job.only_ids = ids;
+        job.files.clear();
+
+        Some(job)
+    }
+}
+
+/// The id of the entry an error line is about, like `dQw4w9WgXcQ` in
+/// `[youtube] dQw4w9WgXcQ: Private video`
+fn item_id(error: &str) -> Option<&str> {
+    let (_, rest) = error.strip_prefix('[')?.split_once("] ")?;
+    let (id, _) = rest.split_once(": ")?;
+
+    (!id.is_empty() && !id.contains(char::is_whitespace)).then_some(id)
+}
+
+impl YtGUI {
+    /// The report of the playlist that just finished
+    pub fn playlist_report(&self) -> Option<iced::widget::Column<'_, Message>> {
+        if self.command.is_running() {
+            return None;
+        }
+
+        let report = self.playlist_report.as_ref()?;
+
+        let mut summary = vec![
+            text(report.summary())
+                .size(14)
+                .width(iced::Length::Fill)
+                .into(),
+        ];
+
+        if !report.failed.is_empty() {
+            summary.push(
+                button(text(fl!("retry_failed_items")).size(14))
+                    .on_press_maybe(
+                        report
+                            .retry_job()
+                            .map(|job| Message::RetryDownload(Box::new(job))),
+                    )
+                    .into(),
+            );
+        }
+
+        let mut view = column![
+            dir_row(summary)
+                .spacing(SPACING)
+                .align_y(iced::Alignment::Center)
+        ]
+        .spacing(SPACING)
+        .padding([0, 12]);
+
+        for item in &report.skipped {
+            view = view.push(
+                text(fl!("playlist_item_skipped", item = item.to_string()))
+                    .size(14)
+                    .style(text::warning),
+            );
+        }
+
+        for item in &report.failed {
+            view = view.push(
+                text(fl!("playlist_item_failed", item = item.to_string()))
+                    .size(14)
+                    .style(text::danger),
+            );
+        }
+
+        Some(view)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_id() {
+        assert_eq!(
+            item_id(
+                "[youtube] dQw4w9WgXcQ: Private video. Sign in if you've been granted access to this video"
+            ),
+            Some("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            item_id("unable to download video data: HTTP Error 403: Forbidden"),
+            None
+        );
+        assert_eq!(item_id("[download] Got error: timed out"), None);
+    }
+}
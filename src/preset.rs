@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{media_options::Options, network::NetworkOptions};
+
+/// A named set of download options
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Preset {
+    pub name: String,
+    pub options: Options,
+    /// Overrides the global network settings when set
+    #[serde(default)]
+    pub network: Option<NetworkOptions>,
+}
@@ -0,0 +1,258 @@
+//! Fetches the metadata of a video with `yt-dlp -J` without downloading it
+//!
+//! The fetched info is saved so format selectors can be tested against it offline
+
+use std::{path::PathBuf, process::Stdio};
+
+use serde::{Deserialize, Serialize};
+
+use crate::sections::format_timestamp;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Chapter {
+    pub title: Option<String>,
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+/// One of the formats a video is available in
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Format {
+    pub format_id: String,
+    pub ext: Option<String>,
+    pub vcodec: Option<String>,
+    pub acodec: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fps: Option<f64>,
+    /// Total bitrate in KBit/s
+    pub tbr: Option<f64>,
+}
+
+impl core::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format_id)?;
+
+        if let Some(ext) = &self.ext {
+            write!(f, " {ext}")?;
+        }
+
+        if let (Some(width), Some(height)) = (self.width, self.height) {
+            write!(f, " {width}x{height}")?;
+        }
+
+        if let Some(fps) = self.fps {
+            write!(f, " {fps}fps")?;
+        }
+
+        // "none" when the format doesn't have video or audio
+        for codec in [&self.vcodec, &self.acodec].into_iter().flatten() {
+            if codec != "none" {
+                write!(f, " {codec}")?;
+            }
+        }
+
+        if let Some(tbr) = self.tbr {
+            write!(f, " {tbr:.0}k")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The fields of yt-dlp's info dict that are used by the app, most of them are optional
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct MediaInfo {
+    pub title: Option<String>,
+    /// Seconds
+    pub duration: Option<f64>,
+    pub is_live: Option<bool>,
+    pub chapters: Option<Vec<Chapter>>,
+    pub uploader: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub track: Option<String>,
+    pub track_number: Option<u32>,
+    pub release_year: Option<u32>,
+    pub upload_date: Option<String>,
+    pub genre: Option<String>,
+    pub formats: Option<Vec<Format>>,
+}
+
+impl MediaInfo {
+    pub fn format(&self, format_id: &str) -> Option<&Format> {
+        self.formats
+            .as_ref()?
+            .iter()
+            .find(|format| format.format_id == format_id)
+    }
+}
+
+impl core::fmt::Display for MediaInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.title.as_deref().unwrap_or("?"))?;
+
+        if let Some(duration) = self.duration {
+            write!(f, " ({})", format_timestamp(duration))?;
+        }
+
+        if let Some(chapters) = self
+            .chapters
+            .as_ref()
+            .filter(|chapters| !chapters.is_empty())
+        {
+            write!(f, ", {} chapters", chapters.len())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("yt-dlp was not found")]
+    YtDlpMissing,
+    #[error("{0}")]
+    Extraction(String),
+    #[error("failed to read the metadata: {0}")]
+    Parse(String),
+    #[error("{0}")]
+    Io(String),
+}
+
+/// Where the info of the last fetched video is saved
+fn info_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_default()
+        .join("ytdlp-gui")
+        .join("info.json")
+}
+
+fn command(bin_path: Option<PathBuf>) -> std::process::Command {
+    #[allow(unused_mut)]
+    let mut command = std::process::Command::new(bin_path.unwrap_or("yt-dlp".into()));
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    command
+}
+
+fn extraction_error(stderr: &[u8], default: &str) -> Error {
+    let stderr = String::from_utf8_lossy(stderr);
+
+    Error::Extraction(
+        stderr
+            .lines()
+            .rev()
+            .find_map(|line| line.strip_prefix("ERROR: "))
+            .unwrap_or(default)
+            .to_string(),
+    )
+}
+
+/// Runs `yt-dlp -J` on `url`, `args` are extra yt-dlp arguments like cookies and network options
+pub async fn fetch_info(
+    bin_path: Option<PathBuf>,
+    url: String,
+    args: Vec<String>,
+) -> Result<MediaInfo, Error> {
+    let (sender, receiver) = iced::futures::channel::oneshot::channel();
+
+    std::thread::spawn(move || {
+        let _ = sender.send(run_probe(bin_path, &url, &args));
+    });
+
+    receiver
+        .await
+        .map_err(|e| Error::Io(e.to_string()))
+        .and_then(|res| res)
+}
+
+fn run_probe(bin_path: Option<PathBuf>, url: &str, args: &[String]) -> Result<MediaInfo, Error> {
+    let output = command(bin_path)
+        .args(["-J", "--no-playlist", "--no-warnings"])
+        .args(args)
+        .arg(url)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|_| Error::YtDlpMissing)?;
+
+    if !output.status.success() {
+        return Err(extraction_error(
+            &output.stderr,
+            "failed to fetch the video info",
+        ));
+    }
+
+    let info = serde_json::from_slice(&output.stdout).map_err(|e| Error::Parse(e.to_string()))?;
+
+    let path = info_path();
+    if let Err(e) = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, &output.stdout))
+    {
+        tracing::error!("failed to save the video info to {}: {e}", path.display());
+    }
+
+    Ok(info)
+}
+
+/// Resolves the `-f`/`-S` arguments of a download against the formats of the last fetched video,
+/// returns the ids of the selected formats, e.g. `["137", "140"]` for `137+140`
+pub async fn resolve_format(
+    bin_path: Option<PathBuf>,
+    format_args: Vec<String>,
+) -> Result<Vec<String>, Error> {
+    let (sender, receiver) = iced::futures::channel::oneshot::channel();
+
+    std::thread::spawn(move || {
+        let _ = sender.send(run_resolve(bin_path, &format_args));
+    });
+
+    receiver
+        .await
+        .map_err(|e| Error::Io(e.to_string()))
+        .and_then(|res| res)
+}
+
+fn run_resolve(bin_path: Option<PathBuf>, format_args: &[String]) -> Result<Vec<String>, Error> {
+    let mut command = command(bin_path);
+
+    command.arg("--load-info-json").arg(info_path()).args([
+        "--simulate",
+        "--no-warnings",
+        "--print",
+        "format_id",
+    ]);
+
+    command.args(format_args);
+
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|_| Error::YtDlpMissing)?;
+
+    if !output.status.success() {
+        return Err(extraction_error(
+            &output.stderr,
+            "failed to resolve the format",
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .flat_map(|line| line.trim().split('+'))
+        .filter(|id| !id.is_empty())
+        .map(ToString::to_string)
+        .collect())
+}
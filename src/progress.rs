@@ -1,16 +1,33 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
 use crate::DownloadError;
 use iced::Length;
 use iced::widget::{button, column, progress_bar, row, space, text};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{Message, YtGUI, app::SPACING};
+use crate::chapters::{SplitChapters, parse_split_line, tag_tracks};
+use crate::history::file_actions;
+use crate::hooks::{self, DownloadedFile};
+use crate::notifications::FinishedVideo;
+use crate::probe::Chapter;
+use crate::ytdlp_error::{self, ErrorCategory};
+use crate::{
+    Message, YtGUI,
+    app::{DownloadType, SPACING},
+    fl,
+};
 
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
 #[serde(tag = "type")]
 #[serde(rename_all = "snake_case")]
 pub enum Progress {
     PreProcessing,
+    /// A yt-dlp warning that's shown to the user
+    Warning(String),
+    /// The livestream hasn't started yet, parsed from `[wait]` lines
+    WaitingForVideo,
     PreDownload {
         video_id: String,
     },
@@ -24,14 +41,78 @@ pub enum Progress {
         playlist_count: Option<i32>,
         playlist_index: Option<i32>,
     },
+    Chapters {
+        chapters: Option<Vec<Chapter>>,
+    },
+    /// Parsed from `[SplitChapters]` lines
+    ChapterSplit {
+        number: u32,
+        destination: PathBuf,
+    },
+    /// The file was moved to its final place
+    Downloaded {
+        resolution: Option<String>,
+        #[serde(default)]
+        filepath: Option<PathBuf>,
+        #[serde(default)]
+        title: Option<String>,
+        #[serde(default)]
+        url: Option<String>,
+        /// The URL of the thumbnail
+        #[serde(default)]
+        thumbnail: Option<String>,
+    },
     EndOfVideo,
     EndOfPlaylist,
     PostProcessing {
-        status: String,
+        status: PostProcessingStatus,
+        /// The name of the postprocessor, like `Merger` or `ExtractAudio`
+        #[serde(default)]
+        postprocessor: Option<String>,
     },
     Error(String),
 }
 
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum PostProcessingStatus {
+    Started,
+    Processing,
+    Finished,
+    #[serde(other)]
+    Unknown,
+}
+
+/// A postprocessing step of the current download, for the history record
+#[derive(Debug)]
+pub struct PostProcessingStep {
+    pub postprocessor: String,
+    pub started: Instant,
+    /// Set when the step finished
+    pub duration: Option<Duration>,
+}
+
+/// What yt-dlp's postprocessors do, the names are the `pp_key`s of yt-dlp
+/// which drop the `FFmpeg` prefix and the `PP` suffix
+pub fn postprocessor_label(postprocessor: &str) -> String {
+    match postprocessor {
+        "Merger" => fl!("pp_merging"),
+        "ExtractAudio" => fl!("pp_extracting_audio"),
+        "EmbedThumbnail" => fl!("pp_embedding_thumbnail"),
+        "ThumbnailsConvertor" => fl!("pp_converting_thumbnail"),
+        "SponsorBlock" => fl!("pp_sponsorblock"),
+        "ModifyChapters" => fl!("pp_modifying_chapters"),
+        "Metadata" => fl!("pp_embedding_metadata"),
+        "EmbedSubtitle" => fl!("pp_embedding_subtitles"),
+        "VideoRemuxer" => fl!("pp_remuxing"),
+        "VideoConvertor" => fl!("pp_recoding"),
+        "SplitChapters" => fl!("pp_splitting_chapters"),
+        "MoveFiles" => fl!("pp_moving_files"),
+        fixup if fixup.starts_with("Fixup") => fl!("pp_fixing_up"),
+        other => fl!("pp_other", postprocessor = other),
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ProgressError {
     #[error("File already exists")]
@@ -44,6 +125,11 @@ pub enum ProgressError {
     VideoUnavailable,
     #[error("Playlist does not exist")]
     NoPlaylist,
+    #[error("{category}")]
+    YtDlp {
+        category: ErrorCategory,
+        error: String,
+    },
     #[error("{0}")]
     Other(String),
 }
@@ -55,6 +141,10 @@ pub fn parse_progress(progress: &str) -> Result<Vec<Progress>, ProgressError> {
         return Err(ProgressError::PlaylistNotChecked);
     } else if progress.contains("Private video. Sign in if you've been granted access to this video") {
         return Err(ProgressError::PrivateVideo);
+    } else if let Some(error) = progress.strip_prefix("stderr:ERROR: ")
+        && let Some(category) = ytdlp_error::classify(error) {
+        tracing::error!("yt-dlp error ({category:?}): {error}");
+        return Err(ProgressError::YtDlp { category, error: error.to_string() });
     } else if progress.contains("Video unavailable. This video contains content") ||
         progress.contains("Video unavailable. This video is no longer available because the YouTube account associated with this video has been terminated.") {
         return Err(ProgressError::VideoUnavailable);
@@ -66,12 +156,29 @@ pub fn parse_progress(progress: &str) -> Result<Vec<Progress>, ProgressError> {
 
     tracing::debug!("received progress from yt-dlp: {progress}");
 
+    if let Some(warning) = progress.strip_prefix("stderr:WARNING: ")
+        && warning.contains("thumbnail")
+    {
+        return Ok(vec![Progress::Warning(warning.to_string())]);
+    }
+
     let mut progresses = Vec::new();
 
+    if progress.trim_start().starts_with("[wait]") {
+        progresses.push(Progress::WaitingForVideo);
+    }
+
     for line in progress.lines() {
+        if let Some((number, destination)) = parse_split_line(line) {
+            progresses.push(Progress::ChapterSplit {
+                number,
+                destination,
+            });
+        }
+
         if line.starts_with("__") {
             for object in line.split("__") {
-                let progress = object.replace("NA", "null");
+                let progress = replace_na(object);
 
                 if let Ok(progress) = serde_json::from_str::<Progress>(&progress) {
                     progresses.push(progress);
@@ -83,6 +190,39 @@ pub fn parse_progress(progress: &str) -> Result<Vec<Progress>, ProgressError> {
     Ok(progresses)
 }
 
+/// yt-dlp writes missing fields as `NA`, this replaces them with `null`
+/// except inside of strings, like titles that contain "NA"
+fn replace_na(json: &str) -> String {
+    let mut replaced = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut chars = json.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if json[i..].starts_with("NA")
+            && !json[..i].ends_with(|c: char| c.is_alphanumeric())
+            && !json[i + 2..].starts_with(|c: char| c.is_alphanumeric())
+        {
+            replaced.push_str("null");
+            chars.next();
+            continue;
+        }
+
+        replaced.push(c);
+    }
+
+    replaced
+}
+
 impl YtGUI {
     pub fn show_download_progress<'a>(
         &'a self,
@@ -108,7 +248,15 @@ impl YtGUI {
                         .padding(12)
                 } else {
                     row![]
-                }
+                },
+                self.download_warning
+                    .as_ref()
+                    .map(|warning| row![text(warning).size(14).style(text::warning)]
+                        .padding([0, 12])),
+                self.hook_status(),
+                self.playlist_report(),
+                self.finished_files(),
+                self.queue_status(),
             ]
             .width(Length::Fill)
             .align_x(iced::Alignment::Center),
@@ -123,11 +271,146 @@ impl YtGUI {
                     .width(iced::Length::Fill)
                     .align_y(iced::Alignment::Center)
                     .padding(12),
+                    self.playlist_report(),
                 ]
             }
         }
     }
 
+    /// Tags the files split from the video that finished downloading, in the background
+    fn tag_split_chapters(&mut self) {
+        let Some(split) = &mut self.split_chapters else {
+            return;
+        };
+
+        if split.tracks.is_empty() {
+            return;
+        }
+
+        let split = SplitChapters {
+            chapters: std::mem::take(&mut split.chapters),
+            tracks: std::mem::take(&mut split.tracks),
+        };
+
+        std::thread::spawn(move || tag_tracks(&split));
+    }
+
+    fn postprocessing(&mut self, status: PostProcessingStatus, postprocessor: Option<String>) {
+        let Some(postprocessor) = postprocessor.filter(|pp| !pp.is_empty() && pp != "NA") else {
+            self.download_message = Some(Ok(String::from("Processing...")));
+            return;
+        };
+
+        match status {
+            PostProcessingStatus::Started => {
+                tracing::info!("postprocessor {postprocessor} started");
+                self.postprocessing_steps.push(PostProcessingStep {
+                    postprocessor: postprocessor.clone(),
+                    started: Instant::now(),
+                    duration: None,
+                });
+            }
+            PostProcessingStatus::Finished => {
+                if let Some(step) = self
+                    .postprocessing_steps
+                    .iter_mut()
+                    .rev()
+                    .find(|step| step.postprocessor == postprocessor && step.duration.is_none())
+                {
+                    let duration = step.started.elapsed();
+                    tracing::info!("postprocessor {postprocessor} finished in {duration:?}");
+                    step.duration = Some(duration);
+                }
+            }
+            PostProcessingStatus::Processing | PostProcessingStatus::Unknown => {}
+        }
+
+        // the loudness is normalized while extracting the audio
+        let normalizing = postprocessor == "ExtractAudio"
+            && self.current_job.as_ref().is_some_and(|job| {
+                job.download_type == DownloadType::Audio
+                    && job.options.loudness.normalize
+                    && job.options.audio_format.reencodes()
+            });
+
+        self.download_message = Some(Ok(if normalizing {
+            fl!("normalizing")
+        } else {
+            postprocessor_label(&postprocessor)
+        }));
+    }
+
+    /// Runs the post-download hooks for the video that just finished
+    fn run_hooks(&mut self) {
+        let Some(file) = self.last_downloaded.take() else {
+            return;
+        };
+
+        let hooks: Vec<String> = self
+            .config
+            .post_download_hooks
+            .iter()
+            .filter(|hook| !hook.trim().is_empty())
+            .cloned()
+            .collect();
+
+        if hooks.is_empty() {
+            return;
+        }
+
+        self.running_hooks += hooks.len();
+        hooks::run_hooks(hooks, file, self.sender.clone());
+    }
+
+    /// The files of the download that just finished
+    fn finished_files(&self) -> Option<iced::widget::Column<'_, Message>> {
+        if self.command.is_running() {
+            return None;
+        }
+
+        let entry = self.history.last().filter(|entry| entry.error.is_none())?;
+
+        Some(entry.job.files.iter().fold(
+            column![].spacing(SPACING).padding([0, 12]),
+            |files, file| files.push(file_actions(file)),
+        ))
+    }
+
+    fn hook_status(&self) -> iced::widget::Column<'_, Message> {
+        let mut status = column![].spacing(SPACING).padding([0, 12]);
+
+        if self.running_hooks > 0 {
+            status = status.push(text(fl!("running_hooks")).size(14));
+        }
+
+        // hook failures are shown apart from the download's result, the file was downloaded
+        for error in &self.hook_errors {
+            status = status.push(
+                text(fl!("hook_failed", error = error.as_str()))
+                    .size(14)
+                    .style(text::danger),
+            );
+        }
+
+        status
+    }
+
+    fn queue_status(&self) -> iced::widget::Row<'_, Message> {
+        let mut status = row![].spacing(SPACING).padding([0, 12]);
+
+        if let Some(rule) = &self.active_rule {
+            status = status.push(text(fl!("active_rule", rule = rule.to_string())).size(14));
+        }
+
+        status = status.push(space::horizontal());
+
+        if !self.queue.is_empty() {
+            status = status.push(text(fl!("queued", count = self.queue.len())).size(14));
+        }
+
+        status
+    }
+
     pub fn handle_progress_event(&mut self, progress: &str) {
         if !self.command.is_running() {
             return;
@@ -136,7 +419,61 @@ impl YtGUI {
         match parse_progress(progress) {
             Ok(progress) => {
                 for progress in progress {
+                    if matches!(progress, Progress::EndOfVideo | Progress::EndOfPlaylist) {
+                        self.tag_split_chapters();
+                    }
+
+                    if progress == Progress::EndOfVideo {
+                        self.run_hooks();
+
+                        if let Some(report) = &mut self.playlist_report {
+                            report.finished_item();
+                        }
+                    }
+
                     match progress {
+                        Progress::Chapters { chapters } => {
+                            if let Some(split) = &mut self.split_chapters {
+                                split.chapters = chapters.unwrap_or_default();
+                                split.tracks.clear();
+                            }
+                        }
+                        Progress::ChapterSplit {
+                            number,
+                            destination,
+                        } => {
+                            if let Some(split) = &mut self.split_chapters {
+                                split.tracks.push((number, destination));
+                            }
+                        }
+                        Progress::Warning(warning) => {
+                            self.download_warning = Some(warning);
+                        }
+                        Progress::WaitingForVideo => {
+                            self.download_message = Some(Ok(fl!("waiting_for_stream")));
+                        }
+                        Progress::PreDownload { video_id } => {
+                            if let Some(report) = &mut self.playlist_report {
+                                report.started_item(video_id);
+                            }
+
+                            if let Some(recording) = &mut self.recording {
+                                recording.started.get_or_insert_with(Instant::now);
+                            }
+                        }
+                        Progress::Downloading {
+                            downloaded_bytes,
+                            speed,
+                            ..
+                        } if self.recording.is_some() => {
+                            // livestreams don't have a total size or an ETA
+                            if let Some(recording) = &mut self.recording {
+                                recording.started.get_or_insert_with(Instant::now);
+                                recording.recorded_bytes = Some(downloaded_bytes);
+                                recording.speed = speed;
+                            }
+                            self.update_recording();
+                        }
                         Progress::Downloading {
                             eta,
                             downloaded_bytes,
@@ -180,24 +517,67 @@ impl YtGUI {
                                 eta_secs = eta.num_seconds() - (eta.num_minutes() * 60),
                             )));
                         }
-                        Progress::PostProcessing { status: _ } => {
-                            self.download_message = Some(Ok(String::from("Processing...")));
+                        Progress::Downloaded {
+                            resolution,
+                            filepath,
+                            title,
+                            url,
+                            thumbnail,
+                        } => {
+                            tracing::info!("downloaded {filepath:?} in {resolution:?}");
+                            self.downloaded_resolution = resolution;
+
+                            if let Some((job, filepath)) =
+                                self.current_job.as_mut().zip(filepath.as_ref())
+                            {
+                                job.files.push(filepath.clone());
+                            }
+
+                            self.finished_videos.push(FinishedVideo {
+                                title: title.clone().unwrap_or_default(),
+                                thumbnail,
+                            });
+
+                            self.last_downloaded = filepath.map(|filepath| DownloadedFile {
+                                filepath,
+                                title: title.unwrap_or_default(),
+                                url: url.unwrap_or_default(),
+                                preset: self
+                                    .current_job
+                                    .as_ref()
+                                    .and_then(|job| job.preset.clone()),
+                            });
                         }
+                        Progress::PostProcessing {
+                            status,
+                            postprocessor,
+                        } => self.postprocessing(status, postprocessor),
                         Progress::EndOfPlaylist => {
                             tracing::info!("end of playlist");
-                            self.end_download(Some(Ok(String::from(
-                                "Playlist download has finished!",
-                            ))));
+                            let message = match &self.playlist_report {
+                                Some(report) if report.has_errors() => report.summary(),
+                                _ => String::from("Playlist download has finished!"),
+                            };
+                            self.end_download(Some(Ok(message)));
                         }
-                        Progress::EndOfVideo => {
-                            if !self.is_playlist {
-                                if self.command.is_multiple_videos() {
-                                    self.command.finished_single_video();
-                                } else {
-                                    self.end_download(Some(Ok(String::from(
-                                        "Download has finished!",
-                                    ))));
-                                }
+                        Progress::EndOfVideo
+                            if !self.current_job.as_ref().is_some_and(|job| job.is_playlist) =>
+                        {
+                            if self.command.is_multiple_videos() {
+                                self.command.finished_single_video();
+                            } else {
+                                let message = match self.downloaded_resolution.take() {
+                                    Some(resolution)
+                                        if self.current_job.as_ref().is_some_and(|job| {
+                                            job.download_type == DownloadType::Video
+                                        }) =>
+                                    {
+                                        fl!("download_finished_resolution", resolution = resolution)
+                                    }
+                                    _ => String::from("Download has finished!"),
+                                };
+
+                                self.end_download(Some(Ok(message)));
                             }
                         }
                         _ => {}
@@ -205,6 +585,29 @@ impl YtGUI {
                 }
             }
             Err(e) => {
+                if self
+                    .recording
+                    .as_ref()
+                    .is_some_and(|recording| recording.stopping)
+                {
+                    // yt-dlp reports the interrupt as an error, the exit of the process ends the download
+                    tracing::debug!("error while stopping the recording: {e}");
+                    return;
+                }
+
+                if let Some(report) = &mut self.playlist_report
+                    && report.record_error(&e, progress)
+                {
+                    tracing::warn!("playlist item error: {e}");
+                    return;
+                }
+
+                if let ProgressError::Other(error) | ProgressError::YtDlp { error, .. } = &e
+                    && self.schedule_retry(error)
+                {
+                    return;
+                }
+
                 self.end_download(Some(Err(DownloadError::Progress(e))));
             }
         }
@@ -216,6 +619,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::excessive_precision)]
     fn test_parsing_progress() {
         let progress = r#"__{"type": "downloading","eta": 10, "downloaded_bytes": 62444041,"total_bytes": 198896641, "total_bytes_estimate": NA,"elapsed": 3.448781967163086, "speed": 12773016.258777222, "playlist_count": NA,"playlist_index": NA }"#;
         let parsed_progress = parse_progress(progress).unwrap();
@@ -234,4 +638,68 @@ mod tests {
             }]
         );
     }
+
+    #[test]
+    fn test_parsing_classified_error() {
+        let progress = "stderr:ERROR: [youtube] dQw4w9WgXcQ: Premieres in 2 hours";
+
+        assert!(matches!(
+            parse_progress(progress),
+            Err(ProgressError::YtDlp {
+                category: ErrorCategory::PremiereNotStarted,
+                ..
+            })
+        ));
+
+        let progress = "stderr:ERROR: something unexpected";
+
+        assert!(matches!(
+            parse_progress(progress),
+            Err(ProgressError::Other(error)) if error == "something unexpected"
+        ));
+    }
+
+    #[test]
+    fn test_parsing_postprocessing() {
+        assert_eq!(
+            parse_progress(
+                r#"__{"type": "post_processing", "status": "started", "postprocessor": "Merger"}"#
+            )
+            .unwrap(),
+            vec![Progress::PostProcessing {
+                status: PostProcessingStatus::Started,
+                postprocessor: Some(String::from("Merger")),
+            }]
+        );
+        assert_eq!(
+            parse_progress(
+                r#"__{"type": "post_processing", "status": "NA", "postprocessor": "NA"}"#
+            )
+            .unwrap(),
+            vec![Progress::PostProcessing {
+                status: PostProcessingStatus::Unknown,
+                postprocessor: Some(String::from("NA")),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_replace_na() {
+        let progress = r#"__{"type": "chapters", "chapters": [{"title": "NASA \"NA\" live", "start_time": 0.0, "end_time": 60.0}]}"#;
+
+        assert_eq!(
+            parse_progress(progress).unwrap(),
+            vec![Progress::Chapters {
+                chapters: Some(vec![Chapter {
+                    title: Some(String::from(r#"NASA "NA" live"#)),
+                    start_time: 0.,
+                    end_time: 60.,
+                }])
+            }]
+        );
+        assert_eq!(
+            replace_na(r#"{"eta": NA, "title": "NA"}"#),
+            r#"{"eta": null, "title": "NA"}"#
+        );
+    }
 }
@@ -0,0 +1,119 @@
+//! Keeps secrets like passwords out of the logs.
+//!
+//! Secrets are registered once they're known, and every log line goes through
+//! [`Redacting`] which replaces them before they're written anywhere.
+
+use std::{
+    borrow::Cow,
+    io,
+    sync::{LazyLock, RwLock},
+};
+
+use tracing_subscriber::fmt::MakeWriter;
+
+const REDACTED: &str = "[redacted]";
+
+static SECRETS: LazyLock<RwLock<Vec<String>>> = LazyLock::new(Default::default);
+
+/// A value that never shows up in `Debug` output
+///
+/// it's not redacted from the logs until it's registered with [`register_secret`],
+/// which is done once the value is final e.g. when it's passed to yt-dlp
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(secret: String) -> Self {
+        Self(secret)
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl core::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+pub fn register_secret(secret: &str) {
+    if secret.is_empty() {
+        return;
+    }
+
+    let Ok(mut secrets) = SECRETS.write() else {
+        return;
+    };
+
+    if !secrets.iter().any(|s| s == secret) {
+        secrets.push(secret.to_string());
+        // replace longer secrets first in case one contains the other
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+    }
+}
+
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let Ok(secrets) = SECRETS.read() else {
+        return Cow::Borrowed(text);
+    };
+
+    let mut text = Cow::Borrowed(text);
+
+    for secret in secrets.iter() {
+        if text.contains(secret.as_str()) {
+            text = Cow::Owned(text.replace(secret.as_str(), REDACTED));
+        }
+    }
+
+    text
+}
+
+/// Wraps a [`MakeWriter`] so everything written through it is redacted
+pub struct Redacting<M>(pub M);
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for Redacting<M> {
+    type Writer = RedactingWriter<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
+    }
+
+    fn make_writer_for(&'a self, meta: &tracing::Metadata<'_>) -> Self::Writer {
+        RedactingWriter(self.0.make_writer_for(meta))
+    }
+}
+
+pub struct RedactingWriter<W>(W);
+
+impl<W: io::Write> io::Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // the fmt layer writes a whole event at once, so secrets won't be split between writes
+        let text = String::from_utf8_lossy(buf);
+        self.0.write_all(redact(&text).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let password = Secret::new(String::from("hunter2"));
+        register_secret(password.expose());
+
+        assert_eq!(
+            redact(r#"["--username", "user", "--password", "hunter2"]"#),
+            r#"["--username", "user", "--password", "[redacted]"]"#
+        );
+        assert_eq!(format!("{password:?}"), "[redacted]");
+        assert_eq!(password.expose(), "hunter2");
+    }
+}
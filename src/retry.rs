@@ -0,0 +1,260 @@
+//! Automatic retries of downloads that failed because of a transient error,
+//! like a server error or a timeout, with an exponential backoff.
+//!
+//! These retries restart yt-dlp, on top of the `--retries` it does by itself.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{YtGUI, download::DownloadJob, fl, ytdlp_error};
+
+/// The longest time between two retries
+const MAX_DELAY: Duration = Duration::from_secs(10 * 60);
+
+/// Errors that are likely to go away by trying again
+const TRANSIENT_ERRORS: &[&str] = &[
+    "HTTP Error 5",
+    "HTTP Error 403",
+    "timed out",
+    "Connection reset",
+    "Connection refused",
+    "Connection aborted",
+    "Remote end closed connection",
+    "IncompleteRead",
+    "Temporary failure in name resolution",
+    // "fragment N not found, unable to continue"
+    "not found, unable to continue",
+    // "Got error: ... Retrying fragment N"
+    "Got error",
+    // "giving up after N fragment retries"
+    "fragment retries",
+];
+
+/// Errors that won't go away by trying again and that aren't known to [`ytdlp_error`],
+/// they take precedence over the transient errors since they can mention HTTP errors as well
+const PERMANENT_ERRORS: &[&str] = &[
+    "Private video",
+    "Video unavailable",
+    "This video is unavailable",
+    "DRM protected",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Transient,
+    Permanent,
+}
+
+/// Unknown errors are permanent so a broken download isn't retried over and over
+pub fn classify(error: &str) -> ErrorKind {
+    if let Some(category) = ytdlp_error::classify(error) {
+        return if category.is_transient() {
+            ErrorKind::Transient
+        } else {
+            ErrorKind::Permanent
+        };
+    }
+
+    let lowercase = error.to_lowercase();
+    let contains = |pattern: &&str| lowercase.contains(&pattern.to_lowercase());
+
+    if PERMANENT_ERRORS.iter().any(contains) {
+        ErrorKind::Permanent
+    } else if TRANSIENT_ERRORS.iter().any(contains) {
+        ErrorKind::Transient
+    } else {
+        ErrorKind::Permanent
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    pub enabled: bool,
+    pub max_retries: u32,
+    /// Seconds before the first retry, doubled for every next one
+    pub initial_delay: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_retries: 5,
+            initial_delay: 15,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The time to wait before the `attempt`th retry, starting from 1
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+
+        Duration::from_secs(u64::from(self.initial_delay) * u64::from(factor)).min(MAX_DELAY)
+    }
+}
+
+/// A failed download waiting to be started again
+#[derive(Debug, Clone)]
+pub struct PendingRetry {
+    pub job: DownloadJob,
+    pub attempt: u32,
+    pub at: Instant,
+}
+
+impl YtGUI {
+    /// Schedules the current download to start again if `error` is transient and
+    /// the retries aren't used up, returns `false` when the download should fail
+    pub fn schedule_retry(&mut self, error: &str) -> bool {
+        let policy = self.config.retry;
+
+        if !policy.enabled
+            || self.retry_attempt >= policy.max_retries
+            || classify(error) == ErrorKind::Permanent
+        {
+            return false;
+        }
+
+        let Some(mut job) = self.current_job.take() else {
+            return false;
+        };
+
+        // the files are recorded again by the next attempt, and the logins written again
+        job.files.clear();
+        job.netrc = None;
+
+        let attempt = self.retry_attempt + 1;
+        let delay = policy.delay(attempt);
+
+        tracing::warn!(
+            "download failed with a transient error, retrying {attempt}/{} in {}s: {error}",
+            policy.max_retries,
+            delay.as_secs()
+        );
+
+        self.command.kill();
+        self.progress = None;
+        self.pending_retry = Some(PendingRetry {
+            job,
+            attempt,
+            at: Instant::now() + delay,
+        });
+        self.update_retry_message();
+
+        true
+    }
+
+    /// Starts the pending retry once it's due, or updates its countdown
+    pub fn retry_tick(&mut self) {
+        if self
+            .pending_retry
+            .as_ref()
+            .is_some_and(|retry| retry.at > Instant::now())
+        {
+            self.update_retry_message();
+            return;
+        }
+
+        let Some(retry) = self.pending_retry.take() else {
+            return;
+        };
+
+        self.start_job(retry.job);
+        self.retry_attempt = retry.attempt;
+    }
+
+    fn update_retry_message(&mut self) {
+        let Some(retry) = &self.pending_retry else {
+            return;
+        };
+
+        let seconds = retry
+            .at
+            .saturating_duration_since(Instant::now())
+            .as_secs_f32()
+            .ceil() as u64;
+
+        self.download_message = Some(Ok(fl!(
+            "retrying",
+            attempt = retry.attempt,
+            max = self.config.retry.max_retries,
+            seconds = seconds
+        )));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let cases = [
+            (
+                "unable to download video data: HTTP Error 503: Service Unavailable",
+                ErrorKind::Transient,
+            ),
+            (
+                "unable to download video data: HTTP Error 403: Forbidden",
+                ErrorKind::Transient,
+            ),
+            (
+                "Unable to download webpage: The read operation timed out",
+                ErrorKind::Transient,
+            ),
+            (
+                "fragment 3 not found, unable to continue",
+                ErrorKind::Transient,
+            ),
+            (
+                "Got error: HTTP Error 404: Not Found. Giving up after 10 fragment retries",
+                ErrorKind::Transient,
+            ),
+            (
+                "[dashsegments] No fragments were found for the requested format",
+                ErrorKind::Permanent,
+            ),
+            (
+                "[hlsnative] This video is DRM protected, its fragments can't be downloaded",
+                ErrorKind::Permanent,
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: Private video. Sign in if you've been granted access to this video",
+                ErrorKind::Permanent,
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: Video unavailable. This video is no longer available",
+                ErrorKind::Permanent,
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: The uploader has not made this video available in your country",
+                ErrorKind::Permanent,
+            ),
+            (
+                "Unsupported URL: https://example.com/",
+                ErrorKind::Permanent,
+            ),
+            ("something unexpected", ErrorKind::Permanent),
+        ];
+
+        for (error, kind) in cases {
+            assert_eq!(classify(error), kind, "{error}");
+        }
+    }
+
+    #[test]
+    fn test_delay() {
+        let policy = RetryPolicy {
+            enabled: true,
+            max_retries: 20,
+            initial_delay: 15,
+        };
+
+        assert_eq!(policy.delay(1), Duration::from_secs(15));
+        assert_eq!(policy.delay(2), Duration::from_secs(30));
+        assert_eq!(policy.delay(3), Duration::from_secs(60));
+        assert_eq!(policy.delay(20), MAX_DELAY);
+    }
+}
@@ -0,0 +1,199 @@
+//! Downloads that start at a given time, they're saved to disk so they survive restarts.
+
+use std::{fs, io, path::PathBuf};
+
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeDelta, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::download::DownloadJob;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ScheduledJob {
+    pub job: DownloadJob,
+    /// Unix timestamp in seconds
+    pub start_at: i64,
+}
+
+impl ScheduledJob {
+    pub fn start_time(&self) -> DateTime<Local> {
+        Local
+            .timestamp_opt(self.start_at, 0)
+            .single()
+            .unwrap_or_else(Local::now)
+    }
+
+    pub fn is_due(&self, now: DateTime<Local>) -> bool {
+        self.start_at <= now.timestamp()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StartTimeError {
+    #[error("Invalid time \"{0}\", use YYYY-MM-DD HH:MM, HH:MM or +2h/+30m")]
+    Invalid(String),
+    #[error("{0} is in the past")]
+    InPast(String),
+}
+
+fn scheduled_jobs_path() -> PathBuf {
+    dirs::data_dir()
+        .expect("data directory")
+        .join("ytdlp-gui/scheduled.json")
+}
+
+pub fn load() -> Vec<ScheduledJob> {
+    match fs::read_to_string(scheduled_jobs_path()) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::error!("failed to parse scheduled downloads: {e}");
+            Vec::new()
+        }),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => {
+            tracing::error!("failed to read scheduled downloads: {e}");
+            Vec::new()
+        }
+    }
+}
+
+pub fn save(jobs: &[ScheduledJob]) -> io::Result<()> {
+    let path = scheduled_jobs_path();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, serde_json::to_string_pretty(jobs)?)
+}
+
+/// Parses the start time of a scheduled download, accepts:
+/// - `YYYY-MM-DD HH:MM`
+/// - `HH:MM` for the next time it's that time of the day
+/// - `+2h`, `+30m` or `+1d` relative to `now`
+pub fn parse_start_time(
+    input: &str,
+    now: DateTime<Local>,
+) -> Result<DateTime<Local>, StartTimeError> {
+    let input = input.trim();
+    let invalid = || StartTimeError::Invalid(input.to_string());
+
+    if let Some(relative) = input.strip_prefix('+') {
+        let (amount, unit) = relative
+            .char_indices()
+            .last()
+            .map(|(i, _)| relative.split_at(i))
+            .ok_or_else(invalid)?;
+        let amount: i64 = amount
+            .parse()
+            .ok()
+            .filter(|amount| *amount > 0)
+            .ok_or_else(invalid)?;
+
+        let duration = match unit {
+            "m" => TimeDelta::try_minutes(amount),
+            "h" => TimeDelta::try_hours(amount),
+            "d" => TimeDelta::try_days(amount),
+            _ => None,
+        };
+
+        return duration
+            .and_then(|duration| now.checked_add_signed(duration))
+            .ok_or_else(invalid);
+    }
+
+    if let Ok(time) = NaiveTime::parse_from_str(input, "%H:%M") {
+        let mut date = now.date_naive();
+        if time <= now.time() {
+            date = date.succ_opt().ok_or_else(invalid)?;
+        }
+
+        return Local
+            .from_local_datetime(&date.and_time(time))
+            .earliest()
+            .ok_or_else(invalid);
+    }
+
+    let date_time =
+        NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M").map_err(|_| invalid())?;
+    let date_time = Local
+        .from_local_datetime(&date_time)
+        .earliest()
+        .ok_or_else(invalid)?;
+
+    if date_time <= now {
+        return Err(StartTimeError::InPast(input.to_string()));
+    }
+
+    Ok(date_time)
+}
+
+/// Formats the time left until a scheduled download starts e.g. `1d 02:03:04`
+pub fn format_countdown(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+
+    let days = seconds / 86400;
+    let hours = seconds % 86400 / 3600;
+    let minutes = seconds % 3600 / 60;
+    let seconds = seconds % 60;
+
+    if days > 0 {
+        format!("{days}d {hours:02}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{hours:02}:{minutes:02}:{seconds:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_start_time() {
+        let now = Local.with_ymd_and_hms(2024, 5, 10, 20, 0, 0).unwrap();
+
+        assert_eq!(
+            parse_start_time("2024-05-11 03:30", now),
+            Ok(Local.with_ymd_and_hms(2024, 5, 11, 3, 30, 0).unwrap())
+        );
+        // earlier in the day means tomorrow
+        assert_eq!(
+            parse_start_time("03:30", now),
+            Ok(Local.with_ymd_and_hms(2024, 5, 11, 3, 30, 0).unwrap())
+        );
+        assert_eq!(
+            parse_start_time("21:00", now),
+            Ok(Local.with_ymd_and_hms(2024, 5, 10, 21, 0, 0).unwrap())
+        );
+        assert_eq!(
+            parse_start_time("+2h", now),
+            Ok(Local.with_ymd_and_hms(2024, 5, 10, 22, 0, 0).unwrap())
+        );
+        assert_eq!(
+            parse_start_time("2024-05-09 03:30", now),
+            Err(StartTimeError::InPast(String::from("2024-05-09 03:30")))
+        );
+        assert_eq!(
+            parse_start_time("tonight", now),
+            Err(StartTimeError::Invalid(String::from("tonight")))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_relative_time() {
+        let now = Local.with_ymd_and_hms(2024, 5, 10, 20, 0, 0).unwrap();
+
+        for input in ["+2ч", "+200000000d", "+-5h", "+0m", "+"] {
+            assert_eq!(
+                parse_start_time(input, now),
+                Err(StartTimeError::Invalid(input.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(59), "00:00:59");
+        assert_eq!(format_countdown(3723), "01:02:03");
+        assert_eq!(format_countdown(90061), "1d 01:01:01");
+        assert_eq!(format_countdown(-5), "00:00:00");
+    }
+}
@@ -0,0 +1,266 @@
+//! Time-of-day bandwidth rules.
+//!
+//! Rules are written one per line in the settings (and in `config.toml`) like:
+//! - `00:00-07:00 unlimited`
+//! - `Mon-Fri 09:00-18:00 2M`
+//! - `Sat,Sun 10:00-18:00 pause`
+//!
+//! the first rule that matches the current time is the active one.
+
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDateTime, Timelike, Weekday};
+
+const MINUTES_IN_DAY: u16 = 24 * 60;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleAction {
+    Unlimited,
+    /// Passed to `--limit-rate` e.g. `2M`
+    Limit(String),
+    Pause,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BandwidthRule {
+    /// Empty means every day
+    days: Vec<Weekday>,
+    /// Minutes since midnight
+    start: u16,
+    /// Minutes since midnight, rules where `end` is before `start` go past midnight
+    end: u16,
+    pub action: RuleAction,
+    source: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("missing time range e.g. 09:00-18:00")]
+    MissingTimeRange,
+    #[error("invalid day \"{0}\", use Mon, Tue, Wed, Thu, Fri, Sat, Sun or a range like Mon-Fri")]
+    InvalidDay(String),
+    #[error("invalid time \"{0}\", use HH:MM")]
+    InvalidTime(String),
+    #[error("missing action, use unlimited, pause or a rate like 2M")]
+    MissingAction,
+    #[error("invalid action \"{0}\", use unlimited, pause or a rate like 2M")]
+    InvalidAction(String),
+}
+
+impl BandwidthRule {
+    pub fn matches(&self, now: NaiveDateTime) -> bool {
+        let minute = (now.hour() * 60 + now.minute()) as u16;
+        let today = now.weekday();
+
+        let on_day = |day: Weekday| self.days.is_empty() || self.days.contains(&day);
+
+        if self.start <= self.end {
+            on_day(today) && (self.start..self.end).contains(&minute)
+        } else {
+            (on_day(today) && minute >= self.start) || (on_day(today.pred()) && minute < self.end)
+        }
+    }
+}
+
+impl core::fmt::Display for BandwidthRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl FromStr for BandwidthRule {
+    type Err = ScheduleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split_whitespace().peekable();
+
+        let days = match tokens.peek() {
+            Some(token) if !token.starts_with(|c: char| c.is_ascii_digit()) => {
+                let days = parse_days(token)?;
+                tokens.next();
+                days
+            }
+            _ => Vec::new(),
+        };
+
+        let (start, end) = tokens
+            .next()
+            .ok_or(ScheduleError::MissingTimeRange)?
+            .split_once('-')
+            .ok_or(ScheduleError::MissingTimeRange)?;
+
+        let start = parse_time(start)?;
+        let end = parse_time(end)?;
+
+        let action = match tokens.next().ok_or(ScheduleError::MissingAction)? {
+            action if action.eq_ignore_ascii_case("unlimited") => RuleAction::Unlimited,
+            action if action.eq_ignore_ascii_case("pause") => RuleAction::Pause,
+            rate if is_rate(rate) => RuleAction::Limit(rate.to_string()),
+            action => return Err(ScheduleError::InvalidAction(action.to_string())),
+        };
+
+        if let Some(token) = tokens.next() {
+            return Err(ScheduleError::InvalidAction(token.to_string()));
+        }
+
+        Ok(BandwidthRule {
+            days,
+            start,
+            end,
+            action,
+            source: s.split_whitespace().collect::<Vec<_>>().join(" "),
+        })
+    }
+}
+
+fn parse_weekday(day: &str) -> Result<Weekday, ScheduleError> {
+    Weekday::from_str(day).map_err(|_| ScheduleError::InvalidDay(day.to_string()))
+}
+
+/// Parses `*`, `Mon`, `Mon-Fri`, `Sat,Sun` or a mix of them like `Mon,Wed-Fri`
+fn parse_days(days: &str) -> Result<Vec<Weekday>, ScheduleError> {
+    if days == "*" {
+        return Ok(Vec::new());
+    }
+
+    let mut parsed = Vec::new();
+
+    for day in days.split(',') {
+        match day.split_once('-') {
+            Some((from, to)) => {
+                let mut day = parse_weekday(from)?;
+                let to = parse_weekday(to)?;
+
+                parsed.push(day);
+                while day != to {
+                    day = day.succ();
+                    parsed.push(day);
+                }
+            }
+            None => parsed.push(parse_weekday(day)?),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_time(time: &str) -> Result<u16, ScheduleError> {
+    let invalid = || ScheduleError::InvalidTime(time.to_string());
+
+    let (hours, minutes) = time.split_once(':').ok_or_else(invalid)?;
+    let hours: u16 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u16 = minutes.parse().map_err(|_| invalid())?;
+
+    // checked before multiplying so large hours don't overflow
+    if hours > 24 || minutes >= 60 {
+        return Err(invalid());
+    }
+
+    let time = hours * 60 + minutes;
+
+    if time > MINUTES_IN_DAY {
+        return Err(invalid());
+    }
+
+    Ok(time)
+}
+
+/// Checks for a `--limit-rate` value like `500K`, `2M` or `1.5M`
+fn is_rate(rate: &str) -> bool {
+    let number = rate.trim_end_matches(['K', 'k', 'M', 'm', 'G', 'g']);
+
+    !number.is_empty() && number.len() + 1 >= rate.len() && number.parse::<f32>().is_ok()
+}
+
+/// Returns the first rule that matches `now`, invalid rules are skipped
+pub fn active_rule(rules: &[String], now: NaiveDateTime) -> Option<BandwidthRule> {
+    rules
+        .iter()
+        .filter_map(|rule| rule.parse::<BandwidthRule>().ok())
+        .find(|rule| rule.matches(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        // 2024-01-01 is a monday
+        NaiveDate::from_ymd_opt(2024, 1, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let rule: BandwidthRule = "Mon-Fri 09:00-18:00 2M".parse().unwrap();
+        assert_eq!(rule.action, RuleAction::Limit(String::from("2M")));
+        assert!(rule.matches(at(1, 9, 0)));
+        assert!(!rule.matches(at(1, 18, 0)));
+        assert!(!rule.matches(at(6, 12, 0)));
+
+        let rule: BandwidthRule = "Sat,Sun 10:00-18:00 pause".parse().unwrap();
+        assert_eq!(rule.action, RuleAction::Pause);
+        assert!(rule.matches(at(6, 10, 30)));
+        assert!(rule.matches(at(7, 17, 59)));
+        assert!(!rule.matches(at(5, 12, 0)));
+
+        assert_eq!(
+            "Mon-Fri 9-18 2M".parse::<BandwidthRule>(),
+            Err(ScheduleError::InvalidTime(String::from("9")))
+        );
+        assert_eq!(
+            "1200:00-1093:00 2M".parse::<BandwidthRule>(),
+            Err(ScheduleError::InvalidTime(String::from("1200:00")))
+        );
+        assert_eq!(
+            "00:00-1093:00 2M".parse::<BandwidthRule>(),
+            Err(ScheduleError::InvalidTime(String::from("1093:00")))
+        );
+        assert_eq!(
+            "Someday 09:00-18:00 2M".parse::<BandwidthRule>(),
+            Err(ScheduleError::InvalidDay(String::from("Someday")))
+        );
+        assert_eq!(
+            "09:00-18:00 fast".parse::<BandwidthRule>(),
+            Err(ScheduleError::InvalidAction(String::from("fast")))
+        );
+        assert_eq!(
+            "09:00-18:00".parse::<BandwidthRule>(),
+            Err(ScheduleError::MissingAction)
+        );
+    }
+
+    #[test]
+    fn test_rule_past_midnight() {
+        let rule: BandwidthRule = "Fri 22:00-02:00 unlimited".parse().unwrap();
+
+        assert!(rule.matches(at(5, 23, 0)));
+        // saturday morning is still part of friday's rule
+        assert!(rule.matches(at(6, 1, 0)));
+        assert!(!rule.matches(at(6, 2, 0)));
+        assert!(!rule.matches(at(4, 23, 0)));
+    }
+
+    #[test]
+    fn test_active_rule() {
+        let rules = [
+            String::from("00:00-07:00 unlimited"),
+            String::from("invalid rule"),
+            String::from("Mon-Fri 09:00-18:00 2M"),
+        ];
+
+        assert_eq!(
+            active_rule(&rules, at(2, 3, 0)).map(|rule| rule.action),
+            Some(RuleAction::Unlimited)
+        );
+        assert_eq!(
+            active_rule(&rules, at(2, 12, 0)).map(|rule| rule.action),
+            Some(RuleAction::Limit(String::from("2M")))
+        );
+        assert_eq!(active_rule(&rules, at(2, 20, 0)), None);
+    }
+}
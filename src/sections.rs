@@ -0,0 +1,241 @@
+//! Parts of a video to download, passed to `--download-sections`.
+//!
+//! Ranges are written like `1:30-2:45, 10:00-inf`, timestamps are `[[HH:]MM:]SS[.ms]`.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct TimeRange {
+    /// Seconds
+    pub start: f64,
+    /// Seconds, `None` means until the end of the video
+    pub end: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SectionError {
+    #[error("invalid timestamp \"{0}\", use [[HH:]MM:]SS")]
+    InvalidTimestamp(String),
+    #[error("invalid range \"{0}\", use START-END e.g. 1:30-2:45 or 10:00-inf")]
+    InvalidRange(String),
+    #[error("the range \"{0}\" ends before it starts")]
+    EmptyRange(String),
+    #[error("the range \"{range}\" is outside of the video's duration ({duration})")]
+    OutOfBounds { range: String, duration: String },
+}
+
+/// Parses `[[HH:]MM:]SS[.ms]` into seconds
+pub fn parse_timestamp(timestamp: &str) -> Result<f64, SectionError> {
+    let invalid = || SectionError::InvalidTimestamp(timestamp.to_string());
+
+    let parts: Vec<&str> = timestamp.trim().split(':').collect();
+
+    if parts.len() > 3 || parts.iter().any(|part| part.is_empty()) {
+        return Err(invalid());
+    }
+
+    let (seconds, rest) = parts.split_last().ok_or_else(invalid)?;
+
+    let seconds: f64 = seconds.parse().map_err(|_| invalid())?;
+    if !seconds.is_finite() || seconds < 0. || (!rest.is_empty() && seconds >= 60.) {
+        return Err(invalid());
+    }
+
+    let mut total = seconds;
+    for (i, part) in rest.iter().rev().enumerate() {
+        let value: u32 = part.parse().map_err(|_| invalid())?;
+
+        // minutes are limited when there are hours
+        if i == 0 && rest.len() == 2 && value >= 60 {
+            return Err(invalid());
+        }
+
+        total += f64::from(value) * 60_f64.powi(i as i32 + 1);
+    }
+
+    Ok(total)
+}
+
+/// Formats seconds as `HH:MM:SS` or `MM:SS`
+pub fn format_timestamp(seconds: f64) -> String {
+    let seconds = seconds.max(0.) as u64;
+
+    if seconds >= 3600 {
+        format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    } else {
+        format!("{:02}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+impl FromStr for TimeRange {
+    type Err = SectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range = s.trim();
+
+        let (start, end) = range
+            .split_once('-')
+            .ok_or_else(|| SectionError::InvalidRange(range.to_string()))?;
+
+        let start = parse_timestamp(start)?;
+        let end = match end.trim() {
+            "inf" | "" => None,
+            end => Some(parse_timestamp(end)?),
+        };
+
+        if end.is_some_and(|end| end <= start) {
+            return Err(SectionError::EmptyRange(range.to_string()));
+        }
+
+        Ok(TimeRange { start, end })
+    }
+}
+
+impl core::fmt::Display for TimeRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.end {
+            Some(end) => write!(
+                f,
+                "{}-{}",
+                format_timestamp(self.start),
+                format_timestamp(end)
+            ),
+            None => write!(f, "{}-inf", format_timestamp(self.start)),
+        }
+    }
+}
+
+impl TimeRange {
+    /// Checks that the range starts and ends within a video of `duration` seconds
+    pub fn check_duration(&self, duration: f64) -> Result<(), SectionError> {
+        // durations are rounded so allow the last second
+        if self.start >= duration || self.end.is_some_and(|end| end > duration.ceil()) {
+            return Err(SectionError::OutOfBounds {
+                range: self.to_string(),
+                duration: format_timestamp(duration),
+            });
+        }
+
+        Ok(())
+    }
+
+    fn option(&self) -> String {
+        match self.end {
+            Some(end) => format!("*{}-{}", self.start, end),
+            None => format!("*{}-inf", self.start),
+        }
+    }
+}
+
+/// Parses comma separated ranges, an empty input means no ranges
+pub fn parse_ranges(input: &str) -> Result<Vec<TimeRange>, SectionError> {
+    input
+        .split(',')
+        .filter(|range| !range.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct DownloadSections {
+    pub ranges: Vec<TimeRange>,
+    /// Regex matched against the chapter titles
+    pub chapters: String,
+    pub force_keyframes_at_cuts: bool,
+}
+
+impl DownloadSections {
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty() && self.chapters.trim().is_empty()
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+
+        for range in &self.ranges {
+            options.extend([String::from("--download-sections"), range.option()]);
+        }
+
+        let chapters = self.chapters.trim();
+        if !chapters.is_empty() {
+            options.extend([String::from("--download-sections"), chapters.to_string()]);
+        }
+
+        if !self.is_empty() && self.force_keyframes_at_cuts {
+            options.push(String::from("--force-keyframes-at-cuts"));
+        }
+
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("90"), Ok(90.));
+        assert_eq!(parse_timestamp("1:30"), Ok(90.));
+        assert_eq!(parse_timestamp("01:02:03.5"), Ok(3723.5));
+        assert_eq!(parse_timestamp("75:00"), Ok(4500.));
+
+        for invalid in ["", "1:", "1:60", "1:60:00", "a:10", "1:2:3:4", "-5"] {
+            assert_eq!(
+                parse_timestamp(invalid),
+                Err(SectionError::InvalidTimestamp(invalid.to_string())),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_ranges() {
+        assert_eq!(
+            parse_ranges("1:30-2:45, 10:00-inf"),
+            Ok(vec![
+                TimeRange {
+                    start: 90.,
+                    end: Some(165.)
+                },
+                TimeRange {
+                    start: 600.,
+                    end: None
+                },
+            ])
+        );
+        assert_eq!(parse_ranges(" "), Ok(Vec::new()));
+        assert_eq!(
+            parse_ranges("2:00-1:00"),
+            Err(SectionError::EmptyRange(String::from("2:00-1:00")))
+        );
+        assert_eq!(
+            parse_ranges("1:00"),
+            Err(SectionError::InvalidRange(String::from("1:00")))
+        );
+    }
+
+    #[test]
+    fn test_check_duration() {
+        let range: TimeRange = "1:00-2:00".parse().unwrap();
+        assert_eq!(range.check_duration(120.), Ok(()));
+        assert_eq!(
+            range.check_duration(100.),
+            Err(SectionError::OutOfBounds {
+                range: String::from("01:00-02:00"),
+                duration: String::from("01:40")
+            })
+        );
+
+        let range: TimeRange = "1:00-inf".parse().unwrap();
+        assert!(range.check_duration(30.).is_err());
+        assert_eq!(range.option(), "*60-inf");
+    }
+}
@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 use crate::fl;
 
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub enum SponsorBlockOption {
     #[default]
     Disabled,
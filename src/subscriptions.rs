@@ -0,0 +1,374 @@
+//! Channel and playlist subscriptions.
+//!
+//! New uploads are found by listing the playlist with `--flat-playlist` and skipping
+//! the entries that are already in the download archive, the new ones are then queued
+//! with `--download-archive` so they're recorded once they're downloaded.
+
+use std::{
+    collections::HashSet,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    process::Stdio,
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Message, YtGUI, app::DownloadType, download::DownloadJob, fl, metadata::Tags, notifications,
+};
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// `<extractor> <id> <url>` for every entry of the playlist
+const ENTRY_TEMPLATE: &str = "%(ie_key,extractor_key)s %(id)s %(url,webpage_url)s";
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ChannelSubscription {
+    pub url: String,
+    pub download_type: DownloadType,
+    pub preset: Option<String>,
+    pub download_folder: PathBuf,
+    /// Add the existing entries to the archive on the first sync instead of downloading them
+    #[serde(default)]
+    pub skip_existing: bool,
+    /// Unix timestamp of the last sync
+    #[serde(default)]
+    pub last_checked: Option<i64>,
+    /// Number of new entries found in the last sync
+    #[serde(default)]
+    pub new_items: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    pub extractor: String,
+    pub id: String,
+    pub url: String,
+}
+
+impl ArchiveEntry {
+    /// The line yt-dlp writes to the download archive for this entry
+    fn archive_line(&self) -> String {
+        format!("{} {}", self.extractor.to_lowercase(), self.id)
+    }
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    #[error("yt-dlp was not found")]
+    YtDlpMissing,
+    #[error("{0}")]
+    Extraction(String),
+    #[error("{0}")]
+    Io(String),
+}
+
+pub fn archive_path() -> PathBuf {
+    dirs::data_dir()
+        .expect("data directory")
+        .join("ytdlp-gui/archive.txt")
+}
+
+fn read_archive() -> io::Result<HashSet<String>> {
+    match fs::read_to_string(archive_path()) {
+        Ok(content) => Ok(content
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(HashSet::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Marks `entries` as downloaded so the next syncs skip them
+pub fn add_to_archive(entries: &[ArchiveEntry]) -> io::Result<()> {
+    let path = archive_path();
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().append(true).create(true).open(path)?;
+
+    for entry in entries {
+        writeln!(file, "{}", entry.archive_line())?;
+    }
+
+    Ok(())
+}
+
+/// Parses the output of `--print` with [`ENTRY_TEMPLATE`], entries without an id are skipped
+fn parse_entries(output: &str) -> Vec<ArchiveEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.trim().splitn(3, ' ');
+            let extractor = parts.next()?;
+            let id = parts.next()?;
+            let url = parts.next()?;
+
+            if [extractor, id, url].contains(&"NA") {
+                return None;
+            }
+
+            Some(ArchiveEntry {
+                extractor: extractor.to_string(),
+                id: id.to_string(),
+                url: url.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Lists the entries of `url` that aren't in the download archive,
+/// `args` are extra yt-dlp arguments like cookies and network options
+pub async fn new_entries(
+    bin_path: Option<PathBuf>,
+    url: String,
+    args: Vec<String>,
+) -> Result<Vec<ArchiveEntry>, Error> {
+    let (sender, receiver) = iced::futures::channel::oneshot::channel();
+
+    std::thread::spawn(move || {
+        let _ = sender.send(list_new_entries(bin_path, &url, &args));
+    });
+
+    receiver
+        .await
+        .map_err(|e| Error::Io(e.to_string()))
+        .and_then(|res| res)
+}
+
+fn list_new_entries(
+    bin_path: Option<PathBuf>,
+    url: &str,
+    args: &[String],
+) -> Result<Vec<ArchiveEntry>, Error> {
+    let mut command = std::process::Command::new(bin_path.unwrap_or("yt-dlp".into()));
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = command
+        .args([
+            "--flat-playlist",
+            "--yes-playlist",
+            "--no-warnings",
+            "--print",
+            ENTRY_TEMPLATE,
+        ])
+        .args(args)
+        .arg(url)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|_| Error::YtDlpMissing)?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        return Err(Error::Extraction(
+            stderr
+                .lines()
+                .rev()
+                .find_map(|line| line.strip_prefix("ERROR: "))
+                .unwrap_or("failed to list the playlist")
+                .to_string(),
+        ));
+    }
+
+    let archive = read_archive().map_err(|e| Error::Io(e.to_string()))?;
+
+    Ok(parse_entries(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .filter(|entry| !archive.contains(&entry.archive_line()))
+        .collect())
+}
+
+impl YtGUI {
+    /// Syncs the subscriptions that weren't checked in the last sync interval
+    pub fn sync_due_subscriptions(&mut self) -> iced::Task<Message> {
+        let now = Local::now().timestamp();
+        let interval = i64::from(self.config.subscription_sync_interval.max(1)) * 60;
+
+        let due: Vec<usize> = self
+            .config
+            .subscriptions
+            .iter()
+            .enumerate()
+            .filter(|(_, subscription)| {
+                subscription
+                    .last_checked
+                    .is_none_or(|last_checked| now - last_checked >= interval)
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        iced::Task::batch(due.into_iter().map(|i| self.sync_subscription(i)))
+    }
+
+    pub fn sync_subscription(&mut self, index: usize) -> iced::Task<Message> {
+        let Some(subscription) = self.config.subscriptions.get(index) else {
+            return iced::Task::none();
+        };
+
+        let url = subscription.url.clone();
+
+        if !self.syncing_subscriptions.insert(url.clone()) {
+            return iced::Task::none();
+        }
+
+        let mut args = self.cookies_args();
+        args.extend(
+            self.config
+                .network_options(subscription.preset.as_deref())
+                .options(),
+        );
+
+        tracing::info!("checking subscription {url} for new entries");
+
+        iced::Task::perform(
+            new_entries(self.config.bin_path.clone(), url.clone(), args),
+            move |res| Message::SubscriptionSynced(url.clone(), res),
+        )
+    }
+
+    /// The links of the running, queued and retried downloads,
+    /// they're not in the archive until they're downloaded
+    fn queued_links(&self) -> HashSet<String> {
+        self.current_job
+            .iter()
+            .chain(&self.queue)
+            .chain(self.pending_retry.as_ref().map(|retry| &retry.job))
+            .flat_map(|job| job.links.iter().cloned())
+            .collect()
+    }
+
+    pub fn subscription_synced(&mut self, url: String, res: Result<Vec<ArchiveEntry>, Error>) {
+        self.syncing_subscriptions.remove(&url);
+
+        let queued = self.queued_links();
+
+        let Some(subscription) = self
+            .config
+            .subscriptions
+            .iter_mut()
+            .find(|subscription| subscription.url == url)
+        else {
+            return;
+        };
+
+        let first_sync = subscription.last_checked.is_none();
+        subscription.last_checked = Some(Local::now().timestamp());
+
+        let mut entries = match res {
+            Ok(entries) => {
+                self.subscription_errors.remove(&url);
+                entries
+            }
+            Err(e) => {
+                tracing::error!("failed to check subscription {url}: {e}");
+                self.subscription_errors.insert(url, e.to_string());
+                let _ = self.save_config();
+                return;
+            }
+        };
+
+        if first_sync && subscription.skip_existing {
+            subscription.new_items = 0;
+
+            if let Err(e) = add_to_archive(&entries) {
+                tracing::error!("failed to write the download archive: {e}");
+            }
+        } else {
+            // queued by an earlier sync
+            entries.retain(|entry| !queued.contains(&entry.url));
+            subscription.new_items = entries.len();
+        }
+
+        let subscription = subscription.clone();
+        let _ = self.save_config();
+
+        if subscription.new_items == 0 {
+            return;
+        }
+
+        tracing::info!("{} new entries in {url}", subscription.new_items);
+
+        if self.config.notifications.subscription_new_items {
+            notifications::notify(&fl!(
+                "subscription_new_items",
+                count = subscription.new_items,
+                url = url.as_str()
+            ));
+        }
+
+        let options = self
+            .config
+            .preset(subscription.preset.as_deref())
+            .map(|preset| preset.options)
+            .unwrap_or(self.config.options);
+
+        self.enqueue_job(DownloadJob {
+            links: entries.into_iter().map(|entry| entry.url).collect(),
+            download_type: subscription.download_type,
+            options,
+            is_playlist: false,
+            live: None,
+            chapters: self.config.chapters.clone(),
+            // the tags of the form are for a single video, keep the ones from the site
+            metadata: (subscription.download_type == DownloadType::Audio
+                && self.config.embed_metadata)
+                .then(Tags::default),
+            sections: Default::default(),
+            get_thumbnail: self.get_thumbnail,
+            thumbnails: self.config.thumbnails,
+            custom_format: Default::default(),
+            files: Vec::new(),
+            only_ids: Vec::new(),
+            netrc: None,
+            sponsorblock: self.sponsorblock,
+            download_folder: subscription.download_folder,
+            preset: subscription.preset,
+            download_archive: Some(archive_path()),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entries() {
+        let output = "Youtube dQw4w9WgXcQ https://www.youtube.com/watch?v=dQw4w9WgXcQ\n\
+                      Youtube NA https://www.youtube.com/watch?v=\n\
+                      \n\
+                      Vimeo 76979871 https://vimeo.com/76979871\n";
+
+        let entries = parse_entries(output);
+
+        assert_eq!(
+            entries,
+            vec![
+                ArchiveEntry {
+                    extractor: String::from("Youtube"),
+                    id: String::from("dQw4w9WgXcQ"),
+                    url: String::from("https://www.youtube.com/watch?v=dQw4w9WgXcQ"),
+                },
+                ArchiveEntry {
+                    extractor: String::from("Vimeo"),
+                    id: String::from("76979871"),
+                    url: String::from("https://vimeo.com/76979871"),
+                },
+            ]
+        );
+        assert_eq!(entries[0].archive_line(), "youtube dQw4w9WgXcQ");
+    }
+}
@@ -53,11 +53,8 @@ pub fn ytdlp_gui_theme(_state: &YtGUI) -> theme::Theme {
 pub fn tab_bar_style(theme: &Theme, status: tab_bar::Status) -> tab_bar::Style {
     let mut base = tab_bar::tab_bar::primary(theme, status);
 
-    match status {
-        tab_bar::Status::Disabled => {
-            base.tab_label_background = Background::Color(SURFACE);
-        }
-        _ => {}
+    if status == tab_bar::Status::Disabled {
+        base.tab_label_background = Background::Color(SURFACE);
     }
 
     base
@@ -0,0 +1,245 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::DownloadType,
+    fl,
+    media_options::{AudioFormat, Options, VideoFormat},
+};
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// The argument of the app that crops a thumbnail, see [`crop_to_square`]
+pub const SQUARE_THUMBNAIL_ARG: &str = "--square-thumbnail";
+
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ThumbnailFormat {
+    /// Whatever format the site provides
+    #[default]
+    Original,
+    Jpg,
+    Png,
+    Webp,
+}
+
+impl ThumbnailFormat {
+    pub const ALL: [ThumbnailFormat; 4] = [
+        ThumbnailFormat::Original,
+        ThumbnailFormat::Jpg,
+        ThumbnailFormat::Png,
+        ThumbnailFormat::Webp,
+    ];
+
+    pub fn options(&self) -> Option<&str> {
+        match self {
+            ThumbnailFormat::Original => None,
+            ThumbnailFormat::Jpg => Some("jpg"),
+            ThumbnailFormat::Png => Some("png"),
+            ThumbnailFormat::Webp => Some("webp"),
+        }
+    }
+}
+
+impl core::fmt::Display for ThumbnailFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThumbnailFormat::Original => f.write_str(&fl!("thumbnail_original_format")),
+            ThumbnailFormat::Jpg => write!(f, "JPG"),
+            ThumbnailFormat::Png => write!(f, "PNG"),
+            ThumbnailFormat::Webp => write!(f, "WEBP"),
+        }
+    }
+}
+
+/// Thumbnail options besides embedding, which is toggled on its own
+#[derive(Deserialize, Serialize, Default, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct ThumbnailOptions {
+    /// Write the thumbnail next to the downloaded file
+    pub write: bool,
+    /// Write every available thumbnail instead of the best one
+    pub write_all: bool,
+    pub format: ThumbnailFormat,
+    /// Crop the thumbnail to a square, only for audio downloads
+    pub square: bool,
+}
+
+impl ThumbnailOptions {
+    pub fn options(&self, embed: bool, download_type: DownloadType) -> Vec<String> {
+        let mut options = Vec::new();
+
+        if embed {
+            options.push(String::from("--embed-thumbnail"));
+        }
+
+        if self.write_all {
+            options.push(String::from("--write-all-thumbnails"));
+        } else if self.write {
+            options.push(String::from("--write-thumbnail"));
+        }
+
+        if !embed && !self.write && !self.write_all {
+            return options;
+        }
+
+        if let Some(format) = self.format.options() {
+            options.extend([String::from("--convert-thumbnails"), format.to_string()]);
+        }
+
+        if self.square && download_type == DownloadType::Audio {
+            options.extend([String::from("--exec"), square_crop_command(&current_exe())]);
+        }
+
+        options
+    }
+}
+
+fn current_exe() -> PathBuf {
+    std::env::current_exe().unwrap_or_else(|_| PathBuf::from("ytdlp-gui"))
+}
+
+/// Runs the app with [`SQUARE_THUMBNAIL_ARG`] on the thumbnail that gets embedded.
+///
+/// It's a separate step since yt-dlp doesn't run the thumbnail conversion when the thumbnail
+/// is already in the chosen format, it runs after the conversion and before the embedding
+fn square_crop_command(exe: &Path) -> String {
+    let exe = exe.to_string_lossy();
+
+    // yt-dlp runs the command in the shell, and `%` starts a field of the output template
+    let exe = if cfg!(target_os = "windows") {
+        format!("\"{exe}\"")
+    } else {
+        format!("'{}'", exe.replace('\'', r#"'"'"'"#))
+    }
+    .replace('%', "%%");
+
+    format!("before_dl:{exe} {SQUARE_THUMBNAIL_ARG} %(thumbnails.-1.filepath)q")
+}
+
+/// Crops the thumbnail at `path` to a square around its center with ffmpeg, for audio cover art.
+///
+/// Missing thumbnails are skipped, yt-dlp passes `NA` when the video doesn't have one
+pub fn crop_to_square(path: &Path) -> io::Result<()> {
+    if !path.is_file() {
+        return Ok(());
+    }
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no file name"))?;
+    let cropped = path.with_file_name(format!(".cropping-{}", file_name.to_string_lossy()));
+
+    let mut command = std::process::Command::new("ffmpeg");
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = command
+        .args(["-y", "-v", "error", "-i"])
+        .arg(path)
+        .args([
+            "-vf",
+            "crop='min(iw,ih)':'min(iw,ih)'",
+            "-frames:v",
+            "1",
+            "-update",
+            "1",
+        ])
+        .arg(&cropped)
+        .stdin(Stdio::null())
+        .output()?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&cropped);
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    std::fs::rename(cropped, path)
+}
+
+/// Whether yt-dlp can embed a thumbnail in the container of the download,
+/// it only prints a warning and moves on when it can't
+pub fn supports_embedding(download_type: DownloadType, options: &Options) -> bool {
+    match download_type {
+        DownloadType::Video => !matches!(
+            options.video_format,
+            VideoFormat::Webm | VideoFormat::Avi | VideoFormat::Flv
+        ),
+        DownloadType::Audio => !matches!(options.audio_format, AudioFormat::Wav | AudioFormat::Aac),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbnail_options() {
+        let thumbnails = ThumbnailOptions {
+            write: true,
+            square: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            thumbnails.options(true, DownloadType::Audio),
+            vec![
+                String::from("--embed-thumbnail"),
+                String::from("--write-thumbnail"),
+                String::from("--exec"),
+                square_crop_command(&current_exe()),
+            ]
+        );
+        // cropped even when the thumbnail is already a jpg and isn't converted
+        assert_eq!(
+            ThumbnailOptions {
+                format: ThumbnailFormat::Jpg,
+                ..thumbnails
+            }
+            .options(true, DownloadType::Audio),
+            vec![
+                String::from("--embed-thumbnail"),
+                String::from("--write-thumbnail"),
+                String::from("--convert-thumbnails"),
+                String::from("jpg"),
+                String::from("--exec"),
+                square_crop_command(&current_exe()),
+            ]
+        );
+        // videos aren't cropped
+        assert_eq!(
+            thumbnails.options(false, DownloadType::Video),
+            vec!["--write-thumbnail"]
+        );
+        assert!(
+            ThumbnailOptions::default()
+                .options(false, DownloadType::Audio)
+                .is_empty()
+        );
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_square_crop_command() {
+        assert_eq!(
+            square_crop_command(Path::new("/opt/100% apps/ytdlp-gui")),
+            "before_dl:'/opt/100%% apps/ytdlp-gui' --square-thumbnail %(thumbnails.-1.filepath)q"
+        );
+    }
+
+    #[test]
+    fn test_crop_missing_thumbnail() {
+        assert!(crop_to_square(Path::new("NA")).is_ok());
+    }
+}
@@ -3,7 +3,7 @@ use std::str::FromStr;
 use reqwest::header::{HeaderMap, HeaderValue};
 use serde::Deserialize;
 
-const CURRENT_VERSION: &'static str = env!("CARGO_PKG_VERSION");
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[derive(thiserror::Error, Debug, Clone)]
 pub enum Error {
@@ -15,6 +15,8 @@ pub enum Error {
     InvalidTagName(String),
     #[error("Invalid version number: {0}")]
     InvalidVersionNumber(String),
+    #[error("Invalid proxy: {0}")]
+    InvalidProxy(String),
 }
 
 #[derive(Debug, Clone)]
@@ -58,14 +60,24 @@ struct Release {
     tag_name: String,
 }
 
-pub async fn check_for_update() -> Result<Option<Version>, Error> {
+/// Checks the latest GitHub release, going through `proxy` if it's set
+pub async fn check_for_update(proxy: Option<String>) -> Result<Option<Version>, Error> {
     let mut headers = HeaderMap::new();
     headers.insert(
         reqwest::header::USER_AGENT,
         HeaderValue::from_str(&format!("ytdlp-gui/{CURRENT_VERSION}")).unwrap(),
     );
 
-    let release = reqwest::Client::new()
+    let mut client = reqwest::Client::builder();
+
+    if let Some(proxy) = proxy {
+        client = client
+            .proxy(reqwest::Proxy::all(&proxy).map_err(|e| Error::InvalidProxy(e.to_string()))?);
+    }
+
+    let release = client
+        .build()
+        .map_err(|e| Error::UpdateFetchFailed(e.to_string()))?
         .get("https://api.github.com/repos/BKSalman/ytdlp-gui/releases/latest")
         .headers(headers)
         .send()
@@ -0,0 +1,260 @@
+//! Sorts the `ERROR:` lines of yt-dlp into known causes, so they can be shown
+//! with a message that says what to do about them.
+
+use crate::fl;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    GeoRestricted,
+    /// Age-restricted videos and videos only available to logged in users
+    LoginRequired,
+    MembersOnly,
+    /// Premieres and scheduled livestreams that didn't start yet
+    PremiereNotStarted,
+    LiveOffline,
+    CopyrightTakedown,
+    UnsupportedUrl,
+    FfmpegMissing,
+    DiskFull,
+    RateLimited,
+}
+
+/// The patterns of each category, matched case-insensitively in this order.
+///
+/// Rate limiting comes first since YouTube's bot check asks to sign in as well
+const PATTERNS: &[(ErrorCategory, &[&str])] = &[
+    (
+        ErrorCategory::RateLimited,
+        &[
+            "HTTP Error 429",
+            "Too Many Requests",
+            "rate-limited",
+            "rate limit",
+            "confirm you’re not a bot",
+            "confirm you're not a bot",
+        ],
+    ),
+    (
+        ErrorCategory::GeoRestricted,
+        &[
+            "geo restriction",
+            "geo-restricted",
+            "available in your country",
+            "not available from your location",
+        ],
+    ),
+    (
+        ErrorCategory::MembersOnly,
+        &["members-only", "channel's members", "members only"],
+    ),
+    (
+        ErrorCategory::LoginRequired,
+        &[
+            "Sign in to confirm your age",
+            "age-restricted",
+            "inappropriate for some users",
+            "only available for registered users",
+            "login required",
+            "requires authentication",
+            "to provide account credentials",
+        ],
+    ),
+    (
+        ErrorCategory::PremiereNotStarted,
+        &[
+            "Premieres in",
+            "Premiere will begin",
+            "live event will begin in",
+        ],
+    ),
+    (
+        ErrorCategory::LiveOffline,
+        &["not currently live", "is offline"],
+    ),
+    (
+        ErrorCategory::CopyrightTakedown,
+        &[
+            "copyright claim",
+            "copyright grounds",
+            "copyright infringement",
+        ],
+    ),
+    (
+        ErrorCategory::UnsupportedUrl,
+        &["Unsupported URL", "is not a valid URL"],
+    ),
+    (
+        ErrorCategory::FfmpegMissing,
+        &[
+            "ffmpeg is not installed",
+            "ffmpeg not found",
+            "ffprobe and ffmpeg not found",
+        ],
+    ),
+    (
+        ErrorCategory::DiskFull,
+        &[
+            "No space left on device",
+            "Errno 28",
+            "not enough space on the disk",
+        ],
+    ),
+];
+
+/// The category of a yt-dlp error line, without the `ERROR: ` prefix
+pub fn classify(error: &str) -> Option<ErrorCategory> {
+    let error = error.to_lowercase();
+
+    PATTERNS.iter().find_map(|(category, patterns)| {
+        patterns
+            .iter()
+            .any(|pattern| error.contains(&pattern.to_lowercase()))
+            .then_some(*category)
+    })
+}
+
+impl ErrorCategory {
+    /// Whether trying again later can work
+    pub fn is_transient(&self) -> bool {
+        matches!(self, ErrorCategory::RateLimited)
+    }
+
+    /// Whether the video itself can't be downloaded, as opposed to a problem on this side
+    pub fn is_unavailable(&self) -> bool {
+        matches!(
+            self,
+            ErrorCategory::GeoRestricted
+                | ErrorCategory::LoginRequired
+                | ErrorCategory::MembersOnly
+                | ErrorCategory::PremiereNotStarted
+                | ErrorCategory::LiveOffline
+                | ErrorCategory::CopyrightTakedown
+        )
+    }
+}
+
+impl core::fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            ErrorCategory::GeoRestricted => fl!("error_geo_restricted"),
+            ErrorCategory::LoginRequired => fl!("error_login_required"),
+            ErrorCategory::MembersOnly => fl!("error_members_only"),
+            ErrorCategory::PremiereNotStarted => fl!("error_premiere_not_started"),
+            ErrorCategory::LiveOffline => fl!("error_live_offline"),
+            ErrorCategory::CopyrightTakedown => fl!("error_copyright_takedown"),
+            ErrorCategory::UnsupportedUrl => fl!("error_unsupported_url"),
+            ErrorCategory::FfmpegMissing => fl!("error_ffmpeg_missing"),
+            ErrorCategory::DiskFull => fl!("error_disk_full"),
+            ErrorCategory::RateLimited => fl!("error_rate_limited"),
+        };
+
+        f.write_str(&message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let cases = [
+            (
+                "[youtube] dQw4w9WgXcQ: The uploader has not made this video available in your country",
+                Some(ErrorCategory::GeoRestricted),
+            ),
+            (
+                "[BiliBili] BV1xx411c7mD: This video is not available from your location due to geo restriction. You might want to use a VPN or a proxy server (with --geo-verification-proxy) to workaround.",
+                Some(ErrorCategory::GeoRestricted),
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: Sign in to confirm your age. This video may be inappropriate for some users. Use --cookies-from-browser or --cookies for the authentication. See  https://github.com/yt-dlp/yt-dlp/wiki/FAQ#how-do-i-pass-cookies-to-yt-dlp  for how to manually pass cookies. Also see  https://github.com/yt-dlp/yt-dlp/wiki/Extractors#exporting-youtube-cookies  for tips on effectively exporting YouTube cookies",
+                Some(ErrorCategory::LoginRequired),
+            ),
+            (
+                "[twitter] 1234567890: NSFW tweet requires authentication. Use --cookies, --cookies-from-browser, --username and --password, --netrc-cmd, or --netrc (twitter) to provide account credentials. See  https://github.com/yt-dlp/yt-dlp/wiki/FAQ#how-do-i-pass-cookies-to-yt-dlp  for how to manually pass cookies",
+                Some(ErrorCategory::LoginRequired),
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: Join this channel to get access to members-only content like this video, and other exclusive perks.",
+                Some(ErrorCategory::MembersOnly),
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: This video is available to this channel's members on level: Supporter (or any higher level). Join this channel to get access to members-only content and other exclusive perks.",
+                Some(ErrorCategory::MembersOnly),
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: Premieres in 2 hours",
+                Some(ErrorCategory::PremiereNotStarted),
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: This live event will begin in 3 days.",
+                Some(ErrorCategory::PremiereNotStarted),
+            ),
+            (
+                "[youtube:tab] @LofiGirl: The channel is not currently live",
+                Some(ErrorCategory::LiveOffline),
+            ),
+            (
+                "[twitch:stream] somechannel: somechannel is offline",
+                Some(ErrorCategory::LiveOffline),
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: Video unavailable. This video is no longer available due to a copyright claim by Some Label",
+                Some(ErrorCategory::CopyrightTakedown),
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: Video unavailable. This video contains content from Some Label, who has blocked it on copyright grounds",
+                Some(ErrorCategory::CopyrightTakedown),
+            ),
+            (
+                "Unsupported URL: https://example.com/",
+                Some(ErrorCategory::UnsupportedUrl),
+            ),
+            (
+                "[generic] 'not-a-link' is not a valid URL. Set --default-search \"ytsearch\" (or run  yt-dlp \"ytsearch:not-a-link\" ) to search YouTube",
+                Some(ErrorCategory::UnsupportedUrl),
+            ),
+            (
+                "You have requested merging of multiple formats but ffmpeg is not installed. Aborting due to --abort-on-error",
+                Some(ErrorCategory::FfmpegMissing),
+            ),
+            (
+                "Postprocessing: ffprobe and ffmpeg not found. Please install or provide the path using --ffmpeg-location",
+                Some(ErrorCategory::FfmpegMissing),
+            ),
+            (
+                "unable to write data: [Errno 28] No space left on device",
+                Some(ErrorCategory::DiskFull),
+            ),
+            (
+                "unable to write data: [WinError 112] There is not enough space on the disk",
+                Some(ErrorCategory::DiskFull),
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: Sign in to confirm you’re not a bot. Use --cookies-from-browser or --cookies for the authentication. See  https://github.com/yt-dlp/yt-dlp/wiki/FAQ#how-do-i-pass-cookies-to-yt-dlp  for how to manually pass cookies",
+                Some(ErrorCategory::RateLimited),
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: This content isn't available, try again later. The current session has been rate-limited by YouTube for up to an hour.",
+                Some(ErrorCategory::RateLimited),
+            ),
+            (
+                "unable to download video data: HTTP Error 429: Too Many Requests",
+                Some(ErrorCategory::RateLimited),
+            ),
+            (
+                "unable to download video data: HTTP Error 503: Service Unavailable",
+                None,
+            ),
+            (
+                "[youtube] dQw4w9WgXcQ: Private video. Sign in if you've been granted access to this video",
+                None,
+            ),
+        ];
+
+        for (error, category) in cases {
+            assert_eq!(classify(error), category, "{error}");
+        }
+    }
+}
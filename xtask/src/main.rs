@@ -344,14 +344,13 @@ fn publish_aur(message: Option<String>) -> anyhow::Result<()> {
 
     let _ = std::fs::remove_dir_all(&temp_aur);
 
-    let clone_output = git("clone")
+    git("clone")
         .with_args([
             "-v",
             &format!("ssh://aur@aur.archlinux.org/{pkgname}.git"),
             "ytdlp-gui-aur",
         ])
         .run_with_inherited_output("Clone AUR package")?;
-    println!("git clone output:\n{:?}", clone_output);
 
     println!("Copying PKGBUILD and .SRCINFO to {}", temp_aur.display());
     std::fs::copy(pkgbuild_path, temp_aur.join("PKGBUILD")).context("failed to copy PKGBUILD")?;
@@ -359,26 +358,23 @@ fn publish_aur(message: Option<String>) -> anyhow::Result<()> {
 
     std::env::set_current_dir(temp_aur)?;
 
-    let add_output = git("add")
+    git("add")
         .with_args(["-v", "."])
         .run_with_inherited_output("Add AUR changes")?;
-    println!("git add output:\n{:?}", add_output);
 
-    let commit_output = git("commit")
+    git("commit")
         .with_args([
             "-v",
             "-m",
             &format!(
                 "Update to {pkgver}-{pkgrel} {}",
-                message.unwrap_or(String::new())
+                message.unwrap_or_default()
             ),
         ])
         .run_with_inherited_output("Commiting AUR changes")
         .context("failed to commit AUR changes")?;
-    println!("git commit output:\n{:?}", commit_output);
 
-    let push_output = git("push").run_with_inherited_output("Pushing to AUR")?;
-    println!("git push output:\n{:?}", push_output);
+    git("push").run_with_inherited_output("Pushing to AUR")?;
 
     Ok(())
 }